@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::debug;
+use typed_builder::TypedBuilder;
+
+use crate::domain::entities::startup_bench::{instrument_profile, BASELINE_SCRIPT};
+use crate::domain::{quote, BenchReport, ProfileWriter, ShellRunner, StartupSample};
+
+/// Measures how long the shell takes to start with and without a profile
+#[derive(TypedBuilder)]
+pub struct BenchService {
+    /// Runner starting a new shell process for every command
+    shell: Arc<dyn ShellRunner>,
+    /// Writes the instrumented profile
+    writer: Arc<dyn ProfileWriter>,
+    /// Launches measured per variant
+    #[builder(default = 10)]
+    runs: usize,
+}
+
+impl BenchService {
+    /// Launch the shell `runs` times without a profile, then `runs` times
+    /// dot-sourcing an instrumented copy of `profile` written to `script`
+    ///
+    /// A script file keeps long profiles clear of the command-line length
+    /// limit, and `$PSScriptRoot` points at its directory.
+    pub async fn run(&self, profile: &str, script: &Path) -> Result<BenchReport> {
+        let mut baseline = Vec::with_capacity(self.runs);
+        for run in 1..=self.runs {
+            let start = Instant::now();
+            self.shell
+                .run(BASELINE_SCRIPT)
+                .await
+                .context("Failed to start the shell without a profile")?;
+            let ms = start.elapsed().as_secs_f64() * 1000.0;
+            debug!("Baseline run {}: {:.0} ms", run, ms);
+            baseline.push(ms);
+        }
+
+        self.writer
+            .write(script, &instrument_profile(profile))
+            .await
+            .context("Failed to write the instrumented profile")?;
+        let command = format!(". {}", quote(&script.to_string_lossy()));
+        let mut samples = Vec::with_capacity(self.runs);
+        for run in 1..=self.runs {
+            let start = Instant::now();
+            let output = self
+                .shell
+                .run(&command)
+                .await
+                .context("Failed to start the shell with the profile")?;
+            let ms = start.elapsed().as_secs_f64() * 1000.0;
+            debug!("Profile run {}: {:.0} ms", run, ms);
+            samples.push(StartupSample::parse(&output, ms)?);
+        }
+
+        Ok(BenchReport::new(&baseline, &samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::FakeShell;
+    use async_trait::async_trait;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryWriter {
+        written: Mutex<Vec<(PathBuf, String)>>,
+    }
+
+    #[async_trait]
+    impl ProfileWriter for MemoryWriter {
+        async fn write(&self, path: &Path, content: &str) -> Result<()> {
+            self.written
+                .lock()
+                .unwrap()
+                .push((path.to_path_buf(), content.to_string()));
+            Ok(())
+        }
+
+        async fn read(&self, _path: &Path) -> Result<String> {
+            Ok(String::new())
+        }
+
+        async fn get_profile_path(&self) -> Result<PathBuf> {
+            Ok(PathBuf::from("/home/me/profile.ps1"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_launches_baseline_and_profile() {
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_run(
+                    ". '/home/me/profile.bench.ps1'",
+                    r#"@@setup_powershell_bench:{"sections":[{"name":"Header","ms":1.5}],"imports":[]}"#,
+                )
+                .on_run(BASELINE_SCRIPT, ""),
+        );
+        let writer = Arc::new(MemoryWriter::default());
+        let service = BenchService::builder()
+            .shell(shell.clone())
+            .writer(writer.clone())
+            .runs(3)
+            .build();
+
+        let script = Path::new("/home/me/profile.bench.ps1");
+        let report = service.run("# --- Header ---\n", script).await.unwrap();
+        assert_eq!(report.runs, 3);
+        assert_eq!(report.sections[0].0, "Header");
+        assert_eq!(report.sections[0].1.median, 1.5);
+        assert_eq!(shell.calls().len(), 6);
+        assert_eq!(shell.calls()[0], BASELINE_SCRIPT);
+        let written = writer.written.lock().unwrap();
+        assert_eq!(written[0].0, script);
+        assert!(written[0].1.contains("$__bench"));
+    }
+}
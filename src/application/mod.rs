@@ -1,3 +1,5 @@
+pub mod bench_service;
 pub mod setup_service;
 
-pub use setup_service::SetupService;
+pub use bench_service::BenchService;
+pub use setup_service::{guarded, SetupService};
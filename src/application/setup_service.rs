@@ -1,9 +1,43 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::Colorize;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, info_span, warn, Instrument};
 use typed_builder::TypedBuilder;
 
-use crate::domain::{Installer, ProfileConfig, ProfileWriter, ShellRunner, TerminalConfigurator};
+use crate::domain::interfaces::{ShellQuery, DEFAULT_INSTALL_TIMEOUT};
+use crate::domain::{
+    CollisionPolicy, ExistingCommand, FailurePolicy, Hook, HookStage, InstallOutcome, Installer,
+    ProfileConfig, ProfileRenderer, ProfileWriter, SetupError, ShellRunner, StartupFile,
+    SyntaxError, TerminalConfigurator,
+};
+
+/// Time allowed for writing the profile or terminal settings
+const FILE_STEP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Run a step, aborting when `cancel` fires or the step exceeds `timeout`
+pub async fn guarded<T>(
+    cancel: &CancellationToken,
+    component: &str,
+    timeout: Duration,
+    step: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        _ = cancel.cancelled() => Err(SetupError::Cancelled.into()),
+        result = tokio::time::timeout(timeout, step) => match result {
+            Ok(result) => result,
+            Err(_) => Err(SetupError::TimedOut {
+                component: component.to_string(),
+                timeout,
+            }
+            .into()),
+        },
+    }
+}
 
 /// Core application service for orchestrating PowerShell setup
 #[derive(TypedBuilder)]
@@ -14,60 +48,198 @@ pub struct SetupService {
     font_installer: Arc<dyn Installer>,
     terminal_config: Arc<dyn TerminalConfigurator>,
     profile_writer: Arc<dyn ProfileWriter>,
+    /// One renderer per shell whose startup files are written, PowerShell first
+    profile_renderers: Vec<Arc<dyn ProfileRenderer>>,
+    /// Prerequisite that makes module installs non-interactive (NuGet provider, PSGallery trust)
+    #[builder(default, setter(strip_option))]
+    module_bootstrapper: Option<Arc<dyn Installer>>,
     #[builder(default)]
     module_installers: Vec<Arc<dyn Installer>>,
+    /// Extra command-line tools installed with the core components
+    #[builder(default)]
+    tool_installers: Vec<Arc<dyn Installer>>,
+    /// User-defined commands run around the setup stages
+    #[builder(default)]
+    hooks: Vec<Hook>,
+    /// Handling of aliases and functions named like existing commands
+    #[builder(default)]
+    collision_policy: CollisionPolicy,
+    /// Timeout overrides keyed by component name
+    #[builder(default)]
+    timeouts: HashMap<String, Duration>,
+    /// Cancelled on Ctrl-C; in-flight steps are dropped, killing their child processes
+    #[builder(default)]
+    cancel: CancellationToken,
+    /// Log file with the output of external processes, shown in the summary
+    #[builder(default, setter(strip_option))]
+    log_path: Option<PathBuf>,
+    #[builder(default, setter(skip))]
+    completed: Mutex<Vec<String>>,
+    /// Startup files written, shown in the summary
+    #[builder(default, setter(skip))]
+    written_profiles: Mutex<Vec<PathBuf>>,
+    /// Components whose installers asked for a restart
+    #[builder(default, setter(skip))]
+    restart_required: Mutex<Vec<String>>,
 }
 
 impl SetupService {
     /// Run the complete setup process
     pub async fn run_setup(&self, config: &ProfileConfig) -> Result<()> {
         // Note: PowerShell installation is handled in main.rs before service creation
-        self.install_core_components().await?;
-        self.install_modules(&config.plugins).await?;
-        self.write_profile(config).await?;
+        if let Err(e) = self.run_steps(config).await {
+            if e.downcast_ref::<SetupError>().is_some() {
+                self.print_interrupted();
+            }
+            return Err(e);
+        }
 
         self.print_success(config).await?;
 
         Ok(())
     }
 
+    async fn run_steps(&self, config: &ProfileConfig) -> Result<()> {
+        async {
+            self.run_hooks(HookStage::BeforeCoreComponents).await?;
+            self.install_core_components(config).await?;
+            self.run_hooks(HookStage::AfterCoreComponents).await
+        }
+        .instrument(info_span!("phase", name = "core_components"))
+        .await?;
+
+        async {
+            self.run_hooks(HookStage::BeforeModules).await?;
+            self.install_modules(&config.plugins).await?;
+            self.run_hooks(HookStage::AfterModules).await
+        }
+        .instrument(info_span!("phase", name = "modules"))
+        .await?;
+
+        async {
+            self.run_hooks(HookStage::BeforeProfile).await?;
+            self.guarded("Profile", FILE_STEP_TIMEOUT, self.write_profile(config))
+                .await?;
+            self.mark_completed("Profile");
+            self.run_hooks(HookStage::AfterProfile).await
+        }
+        .instrument(info_span!("phase", name = "profile"))
+        .await
+    }
+
+    /// Run the hooks configured for a stage, applying each hook's failure policy
+    async fn run_hooks(&self, stage: HookStage) -> Result<()> {
+        for hook in self.hooks.iter().filter(|h| h.stage == stage) {
+            let name = hook.display_name();
+            info!("🪝 Hook: {}", name);
+
+            let script = hook.command.to_script();
+            let result = self
+                .guarded(&name, DEFAULT_INSTALL_TIMEOUT, self.shell.run(&script))
+                .instrument(info_span!("hook", name = %name))
+                .await;
+
+            match result {
+                Ok(_) => self.mark_completed(&format!("Hook {}", name)),
+                Err(e) if matches!(e.downcast_ref(), Some(SetupError::Cancelled)) => return Err(e),
+                Err(e) => match hook.on_failure {
+                    FailurePolicy::Abort => {
+                        return Err(SetupError::HookFailed {
+                            hook: name,
+                            reason: e.to_string(),
+                        }
+                        .into())
+                    }
+                    FailurePolicy::Warn => warn!("Hook {} failed: {}", name, e),
+                    FailurePolicy::Ignore => {}
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run a step, aborting on cancellation or when it exceeds `timeout`
+    async fn guarded<T>(
+        &self,
+        component: &str,
+        timeout: Duration,
+        step: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        guarded(&self.cancel, component, timeout, step).await
+    }
+
+    /// Install a component unless it is already present
+    async fn ensure_installed(&self, installer: &dyn Installer) -> Result<()> {
+        let span = info_span!("installer", component = installer.component_name());
+        self.install_component(installer).instrument(span).await
+    }
+
+    async fn install_component(&self, installer: &dyn Installer) -> Result<()> {
+        let name = installer.component_name();
+        let timeout = self
+            .timeouts
+            .get(name)
+            .copied()
+            .unwrap_or_else(|| installer.timeout());
+
+        // The check and the install share one deadline
+        let outcome = self
+            .guarded(name, timeout, async {
+                if installer.is_installed().await {
+                    Ok(InstallOutcome::AlreadyPresent)
+                } else {
+                    installer.install().await
+                }
+            })
+            .await?;
+
+        match outcome {
+            InstallOutcome::Installed => {}
+            InstallOutcome::AlreadyPresent => info!("✓ {} already installed", name),
+            InstallOutcome::RestartRequired => {
+                self.restart_required.lock().unwrap().push(name.to_string())
+            }
+        }
+
+        self.mark_completed(name);
+        Ok(())
+    }
+
+    fn mark_completed(&self, step: &str) {
+        self.completed.lock().unwrap().push(step.to_string());
+    }
+
     /// Install core components (fonts, terminal config)
-    async fn install_core_components(&self) -> Result<()> {
-        println!(
-            "\n{} {}",
-            "📦".cyan(),
-            "Installing core components...".cyan()
-        );
+    async fn install_core_components(&self, config: &ProfileConfig) -> Result<()> {
+        info!("📦 Installing core components...");
 
         // Install font
-        if !self.font_installer.is_installed().await {
-            self.font_installer.install().await?;
-        } else {
-            println!(
-                "{} {} {}",
-                "✓".green(),
-                self.font_installer.component_name().bright_white(),
-                "already installed".bright_black()
-            );
-        }
+        self.ensure_installed(self.font_installer.as_ref()).await?;
 
         // Configure terminal
         if self.terminal_config.is_supported() {
+            let configure = self.terminal_config.configure_font(config.font.face);
             match self
-                .terminal_config
-                .configure_font("MesloLGM Nerd Font")
+                .guarded("Terminal font", FILE_STEP_TIMEOUT, configure)
                 .await
             {
-                Ok(_) => println!(
-                    "{} {}",
-                    "✅".green(),
-                    "Windows Terminal font updated!".green()
-                ),
-                Err(e) => println!(
-                    "{} {}",
-                    "⚠".yellow(),
-                    format!("Could not configure terminal font: {}", e).yellow()
-                ),
+                Ok(_) => {
+                    info!("✅ Windows Terminal font updated!");
+                    self.mark_completed("Terminal font");
+                }
+                Err(e) if matches!(e.downcast_ref(), Some(SetupError::Cancelled)) => return Err(e),
+                Err(e) => warn!("Could not configure terminal font: {}", e),
+            }
+        }
+
+        // Install extra tools
+        for installer in &self.tool_installers {
+            if let Err(e) = self.ensure_installed(installer.as_ref()).await {
+                if matches!(e.downcast_ref(), Some(SetupError::Cancelled)) {
+                    return Err(e);
+                }
+                warn!("Failed to install {}: {}", installer.component_name(), e);
             }
         }
 
@@ -76,11 +248,9 @@ impl SetupService {
 
     /// Install PowerShell modules
     async fn install_modules(&self, plugins: &[String]) -> Result<()> {
-        println!(
-            "\n{} {}",
-            "🔌".cyan(),
-            "Installing selected plugins...".cyan()
-        );
+        info!("🔌 Installing selected plugins...");
+
+        self.bootstrap_module_sources().await?;
 
         for plugin in plugins {
             // Find installer for this plugin
@@ -89,21 +259,11 @@ impl SetupService {
                 .iter()
                 .find(|i| i.component_name() == plugin)
             {
-                if !installer.is_installed().await {
-                    if let Err(e) = installer.install().await {
-                        eprintln!(
-                            "{} {}",
-                            "⚠".yellow(),
-                            format!("Warning: Failed to install {}: {}", plugin, e).yellow()
-                        );
+                if let Err(e) = self.ensure_installed(installer.as_ref()).await {
+                    if matches!(e.downcast_ref(), Some(SetupError::Cancelled)) {
+                        return Err(e);
                     }
-                } else {
-                    println!(
-                        "{} {} {}",
-                        "✓".green(),
-                        plugin.bright_white(),
-                        "already installed".bright_black()
-                    );
+                    warn!("Failed to install {}: {}", plugin, e);
                 }
             }
         }
@@ -111,114 +271,145 @@ impl SetupService {
         Ok(())
     }
 
-    /// Write the PowerShell profile
-    async fn write_profile(&self, config: &ProfileConfig) -> Result<()> {
-        let profile_path = self.profile_writer.get_profile_path().await?;
-        let content = self.generate_profile_content(config);
+    /// Ensure the module gallery can be used without interactive prompts
+    async fn bootstrap_module_sources(&self) -> Result<()> {
+        match &self.module_bootstrapper {
+            Some(bootstrapper) => self.ensure_installed(bootstrapper.as_ref()).await,
+            None => Ok(()),
+        }
+    }
 
-        self.profile_writer.write(&profile_path, &content).await?;
+    /// Write the PowerShell profile and the startup files of other selected shells
+    async fn write_profile(&self, config: &ProfileConfig) -> Result<()> {
+        let config = &self.resolve_collisions(config).await?;
+        for renderer in &self.profile_renderers {
+            renderer.detect(self.shell.as_ref()).await;
+            for unsupported in renderer.unsupported(config) {
+                warn!("{} profile: {}", renderer.shell_name(), unsupported);
+            }
+            for script in renderer.render(config) {
+                let path = match &script.file {
+                    StartupFile::PowerShellProfile => {
+                        self.profile_writer.get_profile_path().await?
+                    }
+                    StartupFile::Home(relative) => dirs::home_dir()
+                        .context("Could not determine home directory")?
+                        .join(relative),
+                    StartupFile::Config(relative) => dirs::config_dir()
+                        .context("Could not determine configuration directory")?
+                        .join(relative),
+                };
+                match script.file {
+                    StartupFile::PowerShellProfile => {
+                        self.check_syntax(renderer.shell_name(), &script.content, config)
+                            .await?;
+                        self.profile_writer.write(&path, &script.content).await?
+                    }
+                    _ => {
+                        self.profile_writer
+                            .write_block(&path, &script.content)
+                            .await?
+                    }
+                }
+                debug!(
+                    "{} profile written to {}",
+                    renderer.shell_name(),
+                    path.display()
+                );
+                self.written_profiles.lock().unwrap().push(path);
+            }
+        }
 
         Ok(())
     }
 
-    /// Generate profile content
-    fn generate_profile_content(&self, config: &ProfileConfig) -> String {
-        let mut content = String::from(
-            r#"# ===========================
-# Modern PowerShell 7 Profile
-# ===========================
-
-"#,
-        );
-
-        // Import modules
-        content.push_str("# --- Import Modules ---\n");
-        for plugin in &config.plugins {
-            if plugin == "PSReadLine" || plugin == "posh-git" {
-                content.push_str(&format!("Import-Module {}\n", plugin));
-            } else {
-                content.push_str(&format!(
-                    "if (Get-Module -ListAvailable -Name {}) {{ Import-Module {} }}\n",
-                    plugin, plugin
-                ));
-            }
+    /// Parse a PowerShell profile before it replaces the current one, failing
+    /// with its syntax errors located by originating setting
+    async fn check_syntax(&self, shell: &str, profile: &str, config: &ProfileConfig) -> Result<()> {
+        let errors: Vec<SyntaxError> = self
+            .shell
+            .query_as(&SyntaxError::query(profile))
+            .await
+            .context("Failed to parse the generated profile")?;
+        if errors.is_empty() {
+            return Ok(());
         }
-        content.push('\n');
-
-        // Oh-My-Posh theme
-        content.push_str(&format!(
-            r#"# Oh-My-Posh prompt theme
-if (Get-Command oh-my-posh -ErrorAction SilentlyContinue) {{
-    $configPath = "$env:POSH_THEMES_PATH\{}.omp.json"
-    if (Test-Path $configPath) {{
-        oh-my-posh init pwsh --config $configPath | Invoke-Expression
-    }} else {{
-        oh-my-posh init pwsh | Invoke-Expression
-    }}
-}}
-
-"#,
-            config.theme
-        ));
+        Err(SetupError::InvalidProfile {
+            shell: shell.to_string(),
+            errors: errors
+                .iter()
+                .map(|error| error.describe(profile, config))
+                .collect(),
+        }
+        .into())
+    }
 
-        // PSReadLine settings
-        content.push_str(
-            r#"# --- PSReadLine Settings ---
-Set-PSReadLineOption -PredictionSource History
-Set-PSReadLineOption -PredictionViewStyle InlineView
-Set-PSReadLineOption -Colors @{ "InlinePrediction" = 'Cyan' }
-Set-PSReadLineOption -EditMode Windows
-
-# History search with arrow keys
-Set-PSReadLineKeyHandler -Key UpArrow -Function HistorySearchBackward
-Set-PSReadLineKeyHandler -Key DownArrow -Function HistorySearchForward
-
-# Syntax colors
-Set-PSReadLineOption -Colors @{
-    "Command"   = 'Yellow'
-    "Parameter" = 'Green'
-    "String"    = 'Magenta'
-    "Operator"  = 'DarkCyan'
-    "Variable"  = 'White'
-}
+    /// Look up the shortcut names in PowerShell, which sees built-ins and the
+    /// commands of installed modules, and apply the collision policy
+    async fn resolve_collisions(&self, config: &ProfileConfig) -> Result<ProfileConfig> {
+        let mut config = config.clone();
+        let names = self.collision_policy.candidates(&config.groups);
+        if names.is_empty() {
+            return Ok(config);
+        }
+        let existing: Vec<ExistingCommand> = self
+            .shell
+            .query_as(&ExistingCommand::query(&names).to_script())
+            .await
+            .context("Failed to look up existing commands")?;
+        for message in self.collision_policy.apply(&mut config.groups, &existing)? {
+            warn!("{}", message);
+        }
+        Ok(config)
+    }
 
-"#,
-        );
+    /// Report which steps finished before the setup was interrupted
+    fn print_interrupted(&self) {
+        let completed = self.completed.lock().unwrap();
 
-        // Aliases
-        if config.include_aliases {
-            content.push_str(
-                r#"# --- Aliases ---
-Set-Alias ll Get-ChildItem
-function la { Get-ChildItem -Force }
-
-# --- Git Shortcuts ---
-function gs { git status }
-function gcom { git commit @args }
-function gpush { git push @args }
-function gl { git log --oneline --graph --decorate --all }
-function gco { git checkout @args }
-function gb { git branch @args }
-function gd { git diff @args }
-
-"#,
+        println!("\n{}", "━".repeat(60).bright_black());
+        println!("{} {}", "⚠".yellow(), "Setup interrupted".yellow().bold());
+        println!("{}", "━".repeat(60).bright_black());
+        if completed.is_empty() {
+            println!("{}", "No steps were completed.".bright_black());
+        } else {
+            println!(
+                "{} {}",
+                "✓".green(),
+                format!("Completed: {}", completed.join(", ")).bright_white()
             );
         }
+        self.print_restart_required();
+        self.print_log_path();
+    }
 
-        // Environment
-        content.push_str(
-            r#"# --- Environment ---
-$env:POSH_GIT_ENABLED = $true
-"#,
-        );
+    fn print_restart_required(&self) {
+        let restart_required = self.restart_required.lock().unwrap();
+        if !restart_required.is_empty() {
+            println!(
+                "{} {}",
+                "⚠".yellow(),
+                format!(
+                    "Restart Windows to finish installing: {}",
+                    restart_required.join(", ")
+                )
+                .yellow()
+            );
+        }
+    }
 
-        content
+    fn print_log_path(&self) {
+        if let Some(log_path) = &self.log_path {
+            println!(
+                "{} {}",
+                "📝".blue(),
+                format!("Debug log: {}", log_path.display()).bright_white()
+            );
+        }
     }
 
     /// Print success message
     async fn print_success(&self, config: &ProfileConfig) -> Result<()> {
-        let profile_path = self.profile_writer.get_profile_path().await?;
-
         println!("\n{}", "━".repeat(60).bright_black());
         println!(
             "{} {}",
@@ -237,10 +428,20 @@ $env:POSH_GIT_ENABLED = $true
             format!("Plugins: {}", config.plugins.join(", ")).bright_white()
         );
         println!(
-            "\n{} {}",
-            "📄".blue(),
-            format!("Profile written to: {}", profile_path.display()).bright_white()
+            "{} {}",
+            "🔤".blue(),
+            format!("Font: {}", config.font.face).bright_white()
         );
+        println!();
+        for path in self.written_profiles.lock().unwrap().iter() {
+            println!(
+                "{} {}",
+                "📄".blue(),
+                format!("Profile written to: {}", path.display()).bright_white()
+            );
+        }
+        self.print_restart_required();
+        self.print_log_path();
         println!(
             "\n{} {}\n",
             "🔄".cyan(),
@@ -250,3 +451,272 @@ $env:POSH_GIT_ENABLED = $true
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{FunctionDef, PowerShellEdition, PowerShellTarget, ShortcutGroup};
+    use crate::infrastructure::{FakeShell, PowerShellRenderer};
+    use async_trait::async_trait;
+    use std::path::Path;
+
+    /// Installer running `Install-Module` through the shell
+    struct ShellInstaller {
+        name: String,
+        shell: Arc<dyn ShellRunner>,
+    }
+
+    #[async_trait]
+    impl Installer for ShellInstaller {
+        async fn install(&self) -> Result<InstallOutcome> {
+            self.shell
+                .run(&format!("Install-Module {}", self.name))
+                .await?;
+            Ok(InstallOutcome::Installed)
+        }
+
+        async fn is_installed(&self) -> bool {
+            let listed = self
+                .shell
+                .run(&format!("Get-InstalledModule {}", self.name))
+                .await;
+            matches!(listed, Ok(output) if !output.trim().is_empty())
+        }
+
+        fn component_name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    struct Present;
+
+    #[async_trait]
+    impl Installer for Present {
+        async fn install(&self) -> Result<InstallOutcome> {
+            Ok(InstallOutcome::AlreadyPresent)
+        }
+
+        async fn is_installed(&self) -> bool {
+            true
+        }
+
+        fn component_name(&self) -> &str {
+            "Present"
+        }
+    }
+
+    struct NoTerminal;
+
+    #[async_trait]
+    impl TerminalConfigurator for NoTerminal {
+        async fn configure_font(&self, _font_name: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_supported(&self) -> bool {
+            false
+        }
+    }
+
+    /// Keeps written profiles in memory
+    #[derive(Default)]
+    struct MemoryWriter {
+        written: Mutex<Vec<(PathBuf, String)>>,
+    }
+
+    #[async_trait]
+    impl ProfileWriter for MemoryWriter {
+        async fn write(&self, path: &Path, content: &str) -> Result<()> {
+            self.written
+                .lock()
+                .unwrap()
+                .push((path.to_path_buf(), content.to_string()));
+            Ok(())
+        }
+
+        async fn read(&self, _path: &Path) -> Result<String> {
+            Ok(String::new())
+        }
+
+        async fn get_profile_path(&self) -> Result<PathBuf> {
+            Ok(PathBuf::from("/home/me/profile.ps1"))
+        }
+    }
+
+    fn installer(shell: &Arc<FakeShell>) -> Arc<dyn Installer> {
+        Arc::new(ShellInstaller {
+            name: "PSReadLine".to_string(),
+            shell: shell.clone(),
+        })
+    }
+
+    fn service(
+        shell: Arc<FakeShell>,
+        writer: Arc<MemoryWriter>,
+        module_installers: Vec<Arc<dyn Installer>>,
+    ) -> SetupService {
+        service_with(shell, writer, module_installers, CancellationToken::new())
+    }
+
+    fn service_with(
+        shell: Arc<FakeShell>,
+        writer: Arc<MemoryWriter>,
+        module_installers: Vec<Arc<dyn Installer>>,
+        cancel: CancellationToken,
+    ) -> SetupService {
+        SetupService::builder()
+            .shell(shell)
+            .pwsh_installer(Arc::new(Present))
+            .font_installer(Arc::new(Present))
+            .terminal_config(Arc::new(NoTerminal))
+            .profile_writer(writer)
+            .profile_renderers(vec![Arc::new(PowerShellRenderer::new())])
+            .module_installers(module_installers)
+            .timeouts(
+                [("PSReadLine".to_string(), Duration::from_millis(300))]
+                    .into_iter()
+                    .collect(),
+            )
+            .cancel(cancel)
+            .build()
+    }
+
+    fn config() -> ProfileConfig {
+        ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec!["PSReadLine".to_string()])
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_detects_target_after_installing_modules() {
+        let target = PowerShellTarget::new(PowerShellEdition::Desktop).with_psreadline("2.2.0");
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_query("ParseInput", serde_json::json!([]))
+                .on_query("Get-Command", serde_json::json!([]))
+                .on_query(
+                    "PSEdition",
+                    serde_json::json!([{ "Edition": "Desktop", "PSReadLine": "2.2.0" }]),
+                )
+                .on_run("Get-InstalledModule", "")
+                .on_run("Install-Module", ""),
+        );
+        let writer = Arc::new(MemoryWriter::default());
+        service(shell.clone(), writer.clone(), vec![installer(&shell)])
+            .run_setup(&config())
+            .await
+            .unwrap();
+
+        let calls = shell.calls();
+        let installed = calls
+            .iter()
+            .position(|c| c.starts_with("Install-Module"))
+            .unwrap();
+        let detected = calls.iter().position(|c| c.contains("PSEdition")).unwrap();
+        assert!(installed < detected);
+
+        let written = writer.written.lock().unwrap();
+        let expected = PowerShellRenderer::new()
+            .with_target(target)
+            .render(&config());
+        assert_eq!(written[0].1, expected[0].content);
+        assert!(written[0].1.contains("-PredictionViewStyle 'InlineView'"));
+    }
+
+    #[tokio::test]
+    async fn test_check_and_install_share_timeout() {
+        // Each call fits the 300ms timeout on its own, both together do not
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_run("Get-InstalledModule", "")
+                .on_run("Install-Module", "")
+                .with_delay(Duration::from_millis(200)),
+        );
+        let installer = installer(&shell);
+        let error = service(shell.clone(), Arc::default(), vec![])
+            .ensure_installed(installer.as_ref())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(SetupError::TimedOut { component, .. }) if component == "PSReadLine"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_stops_setup() {
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_run("Get-InstalledModule", "")
+                .with_delay(Duration::from_secs(10)),
+        );
+        let writer = Arc::new(MemoryWriter::default());
+        let cancel = CancellationToken::new();
+        tokio::spawn({
+            let cancel = cancel.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                cancel.cancel();
+            }
+        });
+        let error = service_with(
+            shell.clone(),
+            writer.clone(),
+            vec![installer(&shell)],
+            cancel,
+        )
+        .run_setup(&config())
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error.downcast_ref(), Some(SetupError::Cancelled)));
+        assert!(!shell
+            .calls()
+            .iter()
+            .any(|c| c.starts_with("Install-Module")));
+        assert!(writer.written.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_syntax_errors_abort_the_write() {
+        let mut group = ShortcutGroup::new("kube");
+        group.functions.insert(
+            "kwatch".to_string(),
+            FunctionDef::Body {
+                body: "kubectl get pods -w {".to_string(),
+            },
+        );
+        let mut config = config();
+        config.groups.push(group);
+        let profile = &PowerShellRenderer::new().render(&config)[0].content;
+        let line = profile
+            .lines()
+            .position(|line| line == "# --- kube ---")
+            .unwrap()
+            + 2;
+
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_query(
+                    "ParseInput",
+                    serde_json::json!([{ "Line": line, "Column": 1, "Message": "Missing closing '}'" }]),
+                )
+                .on_query("Get-Command", serde_json::json!([]))
+                .on_query("PSEdition", serde_json::json!([]))
+                .on_run("Get-InstalledModule", "PSReadLine"),
+        );
+        let writer = Arc::new(MemoryWriter::default());
+        let error = service(shell.clone(), writer.clone(), vec![installer(&shell)])
+            .run_setup(&config)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(SetupError::InvalidProfile { errors, .. }) if errors[0].contains("in group kube:")
+        ));
+        assert!(writer.written.lock().unwrap().is_empty());
+    }
+}
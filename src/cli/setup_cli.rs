@@ -1,8 +1,11 @@
 use anyhow::Result;
 use colored::Colorize;
 use dialoguer::{Confirm, MultiSelect, Select};
+use std::path::Path;
 
-use crate::domain::{ProfileConfig, ProfilePreset};
+use crate::domain::{
+    BenchReport, InstallScope, NerdFont, ProfileConfig, ProfilePreset, ShortcutGroup,
+};
 
 /// Available Oh-My-Posh themes
 const THEMES: &[(&str, &str)] = &[
@@ -51,6 +54,53 @@ impl SetupCli {
         println!("{}\n", "━".repeat(60).bright_black());
     }
 
+    /// Explain up front which steps need elevation and which fall back to user scope
+    pub fn show_install_scope(&self, scope: InstallScope, module_scope: InstallScope) {
+        let admin = if cfg!(windows) {
+            "administrator"
+        } else {
+            "root"
+        };
+
+        match scope {
+            InstallScope::AllUsers => {
+                println!(
+                    "{} {}",
+                    "🔐".cyan(),
+                    format!("Running as {}, these steps install for all users:", admin).cyan()
+                );
+                println!("   • PowerShell 7 (machine-wide install)");
+                match module_scope {
+                    InstallScope::AllUsers => {
+                        println!("   • PowerShell modules (AllUsers scope, from module_scope)")
+                    }
+                    InstallScope::CurrentUser => println!(
+                        "   • PowerShell modules stay in CurrentUser scope; set module_scope to all_users to share them"
+                    ),
+                }
+            }
+            InstallScope::CurrentUser => {
+                println!(
+                    "{} {}",
+                    "👤".cyan(),
+                    format!(
+                        "Not running as {}, installing for the current user only:",
+                        admin
+                    )
+                    .cyan()
+                );
+                println!("   • PowerShell 7 as a portable copy instead of a machine-wide install");
+                println!("   • PowerShell modules in CurrentUser scope");
+                println!(
+                    "{}",
+                    format!("   Re-run as {} to install these for all users.", admin)
+                        .bright_black()
+                );
+            }
+        }
+        println!("   • Nerd Font in the per-user font directory\n");
+    }
+
     /// Prompt user to install PowerShell 7
     pub fn prompt_install_pwsh(&self) -> Result<bool> {
         println!("{}", "❌ pwsh (PowerShell 7) not found.".red());
@@ -109,6 +159,28 @@ impl SetupCli {
         Ok(THEMES[theme_idx].0.to_string())
     }
 
+    /// Select a Nerd Font
+    pub fn select_font(&self) -> Result<NerdFont> {
+        println!("\n{} {}", "🔤".cyan(), "Choose a Nerd Font:".cyan().bold());
+
+        let font_items: Vec<String> = NerdFont::all()
+            .iter()
+            .map(|f| format!("{} - {}", f.face, f.description))
+            .collect();
+
+        let default = NerdFont::all()
+            .iter()
+            .position(|f| *f == NerdFont::default())
+            .unwrap_or(0);
+        let font_idx = Select::new()
+            .with_prompt("Select font")
+            .items(&font_items)
+            .default(default)
+            .interact()?;
+
+        Ok(NerdFont::all()[font_idx])
+    }
+
     /// Select plugins
     fn select_plugins(&self) -> Result<Vec<String>> {
         println!(
@@ -137,7 +209,13 @@ impl SetupCli {
     }
 
     /// Build profile configuration from preset
-    pub fn build_config_from_preset(&self, preset: &ProfilePreset) -> Result<ProfileConfig> {
+    ///
+    /// `font` comes from the settings file; every preset prompts for one when unset.
+    pub fn build_config_from_preset(
+        &self,
+        preset: &ProfilePreset,
+        font: Option<NerdFont>,
+    ) -> Result<ProfileConfig> {
         let (theme, plugins) = if preset.name == "Custom" {
             self.select_custom_configuration()?
         } else {
@@ -146,12 +224,73 @@ impl SetupCli {
                 preset.plugins.iter().map(|s| s.to_string()).collect(),
             )
         };
+        let font = match font {
+            Some(font) => font,
+            None => self.select_font()?,
+        };
+
+        Ok(Self::preset_config(preset, theme, plugins, font))
+    }
 
-        Ok(ProfileConfig::builder()
+    /// Profile configuration of a preset with the chosen theme, plugins and font
+    fn preset_config(
+        preset: &ProfilePreset,
+        theme: String,
+        plugins: Vec<String>,
+        font: NerdFont,
+    ) -> ProfileConfig {
+        ProfileConfig::builder()
             .theme(theme)
             .plugins(plugins)
-            .include_aliases(preset.include_aliases)
-            .build())
+            .groups(
+                ShortcutGroup::builtin()
+                    .into_iter()
+                    .map(|group| group.enabled(preset.include_aliases))
+                    .collect(),
+            )
+            .font(font)
+            .psreadline((preset.psreadline)())
+            .build()
+    }
+
+    /// Print startup times and the per-section and per-module breakdown
+    pub fn show_bench_report(&self, profile: &Path, report: &BenchReport) {
+        println!(
+            "\n{} {}",
+            "⏱".cyan(),
+            format!(
+                "Startup time of {} over {} runs",
+                profile.display(),
+                report.runs
+            )
+            .cyan()
+            .bold()
+        );
+        println!("{}", "━".repeat(60).bright_black());
+        let row = |name: &str, median: f64, p95: f64| {
+            println!("   {:<32} {:>9.1} ms {:>9.1} ms", name, median, p95)
+        };
+        println!("   {:<32} {:>12} {:>12}", "", "median", "p95");
+        row("No profile", report.baseline.median, report.baseline.p95);
+        row("With profile", report.profile.median, report.profile.p95);
+        println!(
+            "{}",
+            format!("   Profile adds {:.1} ms", report.overhead_ms()).bright_white()
+        );
+
+        if !report.sections.is_empty() {
+            println!("\n   {}", "Sections".bold());
+            for (name, stats) in &report.sections {
+                row(name, stats.median, stats.p95);
+            }
+        }
+        if !report.imports.is_empty() {
+            println!("\n   {}", "Import-Module".bold());
+            for (name, stats) in &report.imports {
+                row(name, stats.median, stats.p95);
+            }
+        }
+        println!();
     }
 }
 
@@ -160,3 +299,22 @@ impl Default for SetupCli {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_config_uses_chosen_font() {
+        for preset in ProfilePreset::all() {
+            let config = SetupCli::preset_config(
+                preset,
+                preset.theme.to_string(),
+                vec![],
+                NerdFont::FIRA_CODE,
+            );
+            assert_eq!(config.font.face, "FiraCode Nerd Font");
+            assert_eq!(config.theme, preset.theme);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use serde::Deserialize;
+
+/// Format of a downloaded release asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+    TarXz,
+    /// The asset is the executable itself
+    Binary,
+}
+
+impl ArchiveKind {
+    /// Infer the format from an asset file name
+    pub fn from_file_name(name: &str) -> ArchiveKind {
+        let name = name.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            ArchiveKind::Zip
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            ArchiveKind::TarGz
+        } else if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+            ArchiveKind::TarXz
+        } else {
+            ArchiveKind::Binary
+        }
+    }
+}
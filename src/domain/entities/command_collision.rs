@@ -0,0 +1,186 @@
+use anyhow::Result;
+use serde::Deserialize;
+
+use super::{PsCommand, ShortcutGroup};
+
+/// What to do with a shortcut named like a command the shell already has
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionPolicy {
+    /// Define the shortcut anyway and print a warning
+    #[default]
+    Warn,
+    /// Stop before the profile is written
+    Refuse,
+    /// Define the shortcut under its name with a trailing `_`
+    Rename,
+}
+
+impl CollisionPolicy {
+    /// Names of the enabled groups' shortcuts to look up, with their
+    /// replacements when renaming
+    pub fn candidates(self, groups: &[ShortcutGroup]) -> Vec<String> {
+        let names = groups
+            .iter()
+            .filter(|group| group.enabled)
+            .flat_map(|group| group.command_names());
+        let mut candidates = vec![];
+        for name in names {
+            candidates.push(name.to_string());
+            if self == CollisionPolicy::Rename {
+                candidates.push(renamed(name));
+            }
+        }
+        candidates
+    }
+
+    /// Apply the policy to shortcuts of enabled groups named like one of
+    /// `existing`, returning a message per collision
+    pub fn apply(
+        self,
+        groups: &mut [ShortcutGroup],
+        existing: &[ExistingCommand],
+    ) -> Result<Vec<String>> {
+        let find = |name: &str| {
+            // PowerShell command names are case-insensitive
+            existing
+                .iter()
+                .find(|command| command.name.eq_ignore_ascii_case(name))
+        };
+
+        let mut messages = vec![];
+        for group in groups.iter_mut().filter(|group| group.enabled) {
+            let names: Vec<String> = group.command_names().map(str::to_string).collect();
+            for name in names {
+                let Some(command) = find(&name) else {
+                    continue;
+                };
+                let collision = format!(
+                    "{} of group {} collides with {}",
+                    name,
+                    group.name,
+                    command.describe()
+                );
+                match self {
+                    CollisionPolicy::Warn | CollisionPolicy::Refuse => messages.push(collision),
+                    CollisionPolicy::Rename => {
+                        let new_name = renamed(&name);
+                        if let Some(taken) = find(&new_name) {
+                            anyhow::bail!(
+                                "{} and cannot be renamed: {} is a {} too",
+                                collision,
+                                new_name,
+                                taken.describe()
+                            );
+                        }
+                        group.rename(&name, &new_name);
+                        messages.push(format!("{}, defined as {}", collision, new_name));
+                    }
+                }
+            }
+        }
+
+        if self == CollisionPolicy::Refuse && !messages.is_empty() {
+            anyhow::bail!(
+                "Shortcuts collide with existing commands (rename them or set alias_collisions to warn or rename):\n  {}",
+                messages.join("\n  ")
+            );
+        }
+        Ok(messages)
+    }
+}
+
+fn renamed(name: &str) -> String {
+    format!("{}_", name)
+}
+
+/// Command already known to PowerShell, as reported by `Get-Command`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExistingCommand {
+    pub name: String,
+    /// `Alias`, `Function`, `Cmdlet` or `Application`
+    pub command_type: String,
+    /// Module or executable path defining the command
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl ExistingCommand {
+    /// Query listing the commands, including those of installed modules, with
+    /// one of `names`
+    pub fn query(names: &[String]) -> PsCommand {
+        PsCommand::new("Get-Command")
+            .list("Name", names)
+            .param("ErrorAction", "SilentlyContinue")
+            .pipe(
+                "Select-Object Name, \
+                 @{ Name = 'CommandType'; Expression = { \"$($_.CommandType)\" } }, Source",
+            )
+    }
+
+    /// e.g. `Alias from Microsoft.PowerShell.Management`
+    pub fn describe(&self) -> String {
+        match self.source.as_deref().filter(|source| !source.is_empty()) {
+            Some(source) => format!("{} from {}", self.command_type, source),
+            None => self.command_type.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str, command_type: &str, source: &str) -> ExistingCommand {
+        ExistingCommand {
+            name: name.to_string(),
+            command_type: command_type.to_string(),
+            source: Some(source.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_applies_policy_to_collisions() {
+        let existing = [
+            command("GL", "Alias", "Microsoft.PowerShell.Management"),
+            command("la", "Application", "/usr/bin/la"),
+        ];
+        let groups = ShortcutGroup::builtin();
+
+        let mut warned = groups.clone();
+        let messages = CollisionPolicy::Warn.apply(&mut warned, &existing).unwrap();
+        assert_eq!(warned, groups);
+        assert_eq!(
+            messages,
+            vec![
+                "la of group listing collides with Application from /usr/bin/la",
+                "gl of group git collides with Alias from Microsoft.PowerShell.Management",
+            ]
+        );
+
+        let error = CollisionPolicy::Refuse
+            .apply(&mut groups.clone(), &existing)
+            .unwrap_err();
+        assert!(error.to_string().contains("gl of group git"));
+
+        let mut renamed = groups.clone();
+        CollisionPolicy::Rename
+            .apply(&mut renamed, &existing)
+            .unwrap();
+        assert!(renamed[0].aliases.contains_key("la_"));
+        assert!(renamed[1].functions.contains_key("gl_"));
+        assert!(!renamed[1].functions.contains_key("gl"));
+        assert!(CollisionPolicy::Rename
+            .candidates(&groups)
+            .contains(&"gl_".to_string()));
+
+        let taken = [
+            command("gs", "Application", "/usr/bin/gs"),
+            command("gs_", "Function", ""),
+        ];
+        assert!(CollisionPolicy::Rename
+            .apply(&mut groups.clone(), &taken)
+            .is_err());
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use typed_builder::TypedBuilder;
+
+/// Progress notification emitted while extracting an archive
+#[derive(Debug, Clone)]
+pub enum ExtractEvent {
+    /// A file was written to `path`
+    Extracted { path: PathBuf, bytes: u64 },
+    /// An entry did not match the selection
+    Skipped { entry: PathBuf },
+}
+
+/// Callback receiving extraction progress
+pub type ExtractProgress = Arc<dyn Fn(&ExtractEvent) + Send + Sync>;
+
+/// Selection and layout of extracted entries
+#[derive(Clone, Default, TypedBuilder)]
+pub struct ExtractOptions {
+    /// Glob patterns matched against entry paths; empty extracts everything
+    #[builder(default)]
+    pub include: Vec<String>,
+    /// Write files directly into the destination, dropping archive folders
+    #[builder(default)]
+    pub flatten: bool,
+    #[builder(default, setter(strip_option))]
+    pub progress: Option<ExtractProgress>,
+}
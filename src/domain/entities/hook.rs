@@ -0,0 +1,104 @@
+use serde::Deserialize;
+
+use super::PsCommand;
+
+/// Point in the setup at which a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookStage {
+    BeforeCoreComponents,
+    AfterCoreComponents,
+    BeforeModules,
+    AfterModules,
+    BeforeProfile,
+    AfterProfile,
+}
+
+/// What to do when a hook fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Stop the setup
+    #[default]
+    Abort,
+    /// Print a warning and continue
+    Warn,
+    /// Continue silently
+    Ignore,
+}
+
+/// Command executed by a hook
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum HookCommand {
+    /// PowerShell script text
+    PowerShell { powershell: String },
+    /// Native executable with arguments
+    Native {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl HookCommand {
+    /// PowerShell script running this command; native commands fail on a non-zero exit code
+    pub fn to_script(&self) -> String {
+        match self {
+            HookCommand::PowerShell { powershell } => powershell.clone(),
+            HookCommand::Native { command, args } => {
+                let script = PsCommand::native(command).args(args).to_script();
+                format!("{}\nif ($LASTEXITCODE) {{ exit $LASTEXITCODE }}", script)
+            }
+        }
+    }
+}
+
+/// User-defined command run at a setup stage
+#[derive(Debug, Clone, Deserialize)]
+pub struct Hook {
+    pub name: Option<String>,
+    pub stage: HookStage,
+    #[serde(flatten)]
+    pub command: HookCommand,
+    #[serde(default)]
+    pub on_failure: FailurePolicy,
+}
+
+impl Hook {
+    /// Name shown in output: the configured name or the command itself
+    pub fn display_name(&self) -> String {
+        match (&self.name, &self.command) {
+            (Some(name), _) => name.clone(),
+            (None, HookCommand::PowerShell { powershell }) => {
+                powershell.lines().next().unwrap_or_default().to_string()
+            }
+            (None, HookCommand::Native { command, .. }) => command.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hooks() {
+        let hooks: Vec<Hook> = serde_json::from_str(
+            r#"[
+                { "stage": "before_modules", "powershell": "Register-PSRepository -Default" },
+                { "name": "logo", "stage": "after_profile", "command": "robocopy",
+                  "args": ["C:\\it's", "D:\\"], "on_failure": "warn" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(hooks[0].stage, HookStage::BeforeModules);
+        assert_eq!(hooks[0].on_failure, FailurePolicy::Abort);
+        assert_eq!(hooks[1].on_failure, FailurePolicy::Warn);
+        assert_eq!(
+            hooks[1].command.to_script(),
+            "& 'robocopy' 'C:\\it''s' 'D:\\'\nif ($LASTEXITCODE) { exit $LASTEXITCODE }"
+        );
+    }
+}
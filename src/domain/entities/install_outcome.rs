@@ -0,0 +1,10 @@
+/// Result of a successful install step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallOutcome {
+    /// The component was installed
+    Installed,
+    /// The component was installed but a restart is needed to finish
+    RestartRequired,
+    /// The installer found the component already present
+    AlreadyPresent,
+}
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+/// Where components are installed, chosen from the privileges of the process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallScope {
+    /// Per-user locations that never need elevation
+    #[default]
+    CurrentUser,
+    /// Machine-wide locations; needs admin rights on Windows or root on Linux
+    AllUsers,
+}
+
+impl InstallScope {
+    /// Machine-wide when elevated, per-user otherwise
+    pub fn for_elevation(elevated: bool) -> Self {
+        if elevated {
+            Self::AllUsers
+        } else {
+            Self::CurrentUser
+        }
+    }
+
+    /// Value of the PowerShell `-Scope` parameter
+    pub fn as_powershell(&self) -> &'static str {
+        match self {
+            Self::CurrentUser => "CurrentUser",
+            Self::AllUsers => "AllUsers",
+        }
+    }
+}
@@ -0,0 +1,51 @@
+/// First line of the block this tool manages in shared startup files
+pub const BLOCK_BEGIN: &str = "# >>> setup_powershell >>>";
+/// Last line of the managed block
+pub const BLOCK_END: &str = "# <<< setup_powershell <<<";
+
+/// `existing` with its managed block replaced by `content`, or with a new
+/// block appended when it has none
+pub fn splice_managed_block(existing: &str, content: &str) -> String {
+    let block = format!("{}\n{}\n{}\n", BLOCK_BEGIN, content.trim(), BLOCK_END);
+
+    let begin = existing.find(BLOCK_BEGIN);
+    let end = begin.and_then(|begin| {
+        existing[begin..]
+            .find(BLOCK_END)
+            .map(|end| begin + end + BLOCK_END.len())
+    });
+
+    match (begin, end) {
+        (Some(begin), Some(end)) => {
+            let rest = existing[end..]
+                .strip_prefix('\n')
+                .unwrap_or(&existing[end..]);
+            format!("{}{}{}", &existing[..begin], block, rest)
+        }
+        _ if existing.trim().is_empty() => block,
+        _ => format!("{}\n\n{}", existing.trim_end(), block),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appends_then_replaces_block() {
+        let original = "export EDITOR=vim\n";
+        let first = splice_managed_block(original, "alias ll='ls -l'");
+        assert_eq!(
+            first,
+            "export EDITOR=vim\n\n# >>> setup_powershell >>>\nalias ll='ls -l'\n# <<< setup_powershell <<<\n"
+        );
+
+        let edited = format!("{}export PAGER=less\n", first);
+        let second = splice_managed_block(&edited, "alias la='ls -A'");
+        assert_eq!(
+            second,
+            "export EDITOR=vim\n\n# >>> setup_powershell >>>\nalias la='ls -A'\n# <<< setup_powershell <<<\nexport PAGER=less\n"
+        );
+        assert_eq!(splice_managed_block(&second, "alias la='ls -A'"), second);
+    }
+}
@@ -1,5 +1,50 @@
+pub mod archive_kind;
+pub mod command_collision;
+pub mod extraction;
+pub mod hook;
+pub mod install_outcome;
+pub mod install_scope;
+pub mod managed_block;
+pub mod module_backend;
+pub mod module_info;
+pub mod module_loading;
+pub mod nerd_font;
+pub mod powershell_target;
 pub mod preset;
 pub mod profile;
+pub mod profile_script;
+pub mod profile_syntax;
+pub mod ps_command;
+pub mod psreadline;
+pub mod settings;
+pub mod shortcut_group;
+pub mod startup_bench;
+pub mod tool;
+pub mod verbosity;
 
+pub use archive_kind::ArchiveKind;
+pub use command_collision::{CollisionPolicy, ExistingCommand};
+pub use extraction::{ExtractEvent, ExtractOptions, ExtractProgress};
+pub use hook::{FailurePolicy, Hook, HookCommand, HookStage};
+pub use install_outcome::InstallOutcome;
+pub use install_scope::InstallScope;
+pub use managed_block::splice_managed_block;
+pub use module_backend::ModuleBackend;
+pub use module_info::ModuleInfo;
+pub use module_loading::ModuleLoading;
+pub use nerd_font::NerdFont;
+pub use powershell_target::{PowerShellEdition, PowerShellTarget};
 pub use preset::ProfilePreset;
 pub use profile::ProfileConfig;
+pub use profile_script::{ProfileScript, ProfileShell, StartupFile};
+pub use profile_syntax::SyntaxError;
+pub use ps_command::{quote, PsCommand};
+pub use psreadline::{
+    BellStyle, ColorToken, EditMode, HistorySaveStyle, KeyAction, KeyHandler, PSReadLineConfig,
+    PredictionSource, PredictionViewStyle,
+};
+pub use settings::Settings;
+pub use shortcut_group::{AliasTarget, FunctionDef, ShortcutGroup, ShortcutGroupOverride};
+pub use startup_bench::{BenchOptions, BenchReport, StartupSample, StartupTiming, Stats};
+pub use tool::ToolSpec;
+pub use verbosity::Verbosity;
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+/// Backend used to install PowerShell modules; both detect them with `Get-Module -ListAvailable`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModuleBackend {
+    /// Use PSResourceGet when available, PowerShellGet otherwise
+    #[default]
+    Auto,
+    /// PowerShellGet v2 (`Install-Module`)
+    PowerShellGet,
+    /// PSResourceGet (`Install-PSResource`)
+    PSResourceGet,
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// Installed PowerShell module as reported by `Get-Module -ListAvailable`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ModuleInfo {
+    pub name: String,
+    pub version: String,
+    /// Manifest path or install location
+    #[serde(default)]
+    pub path: Option<String>,
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use serde::Deserialize;
+
+/// When the PowerShell profile imports a plugin module
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleLoading {
+    /// At startup, before the first prompt
+    Eager,
+    /// Once the shell is idle after the first prompt
+    Idle,
+    /// On the first call of one of the listed commands
+    OnDemand(Vec<String>),
+}
+
+impl ModuleLoading {
+    /// Loading used when the settings do not name one: modules the prompt or
+    /// key bindings need are eager, known slow ones without either are idle
+    pub fn default_for(module: &str) -> Self {
+        match module {
+            "Terminal-Icons" | "z" => ModuleLoading::Idle,
+            _ => ModuleLoading::Eager,
+        }
+    }
+
+    /// Reject loadings the profile cannot honor for `module`
+    pub fn validate(&self, module: &str) -> Result<()> {
+        match self {
+            ModuleLoading::Eager => {}
+            // Set-PSReadLineOption runs at startup and would import it anyway
+            _ if module == "PSReadLine" => {
+                anyhow::bail!("PSReadLine is configured at startup and must load eagerly")
+            }
+            ModuleLoading::Idle => {}
+            ModuleLoading::OnDemand(commands) => {
+                if commands.is_empty() {
+                    anyhow::bail!("on_demand loading of {} needs at least one command", module);
+                }
+                for command in commands {
+                    let valid = command
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+                    if command.is_empty() || !valid {
+                        anyhow::bail!("Invalid command name {:?} for module {}", command, module);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parses_and_validates() {
+        let loading: HashMap<String, ModuleLoading> = serde_json::from_str(
+            r#"{ "posh-git": "eager", "Terminal-Icons": "idle", "z": { "on_demand": ["z", "zz"] } }"#,
+        )
+        .unwrap();
+        assert_eq!(loading["posh-git"], ModuleLoading::Eager);
+        assert_eq!(
+            loading["z"],
+            ModuleLoading::OnDemand(vec!["z".to_string(), "zz".to_string()])
+        );
+        for (module, loading) in &loading {
+            loading.validate(module).unwrap();
+        }
+
+        assert!(ModuleLoading::Idle.validate("PSReadLine").is_err());
+        assert!(ModuleLoading::OnDemand(vec![]).validate("z").is_err());
+        assert!(ModuleLoading::OnDemand(vec!["z; exit".to_string()])
+            .validate("z")
+            .is_err());
+    }
+
+    #[test]
+    fn test_defaults_keep_prompt_and_key_binding_modules_eager() {
+        for module in ["PSReadLine", "posh-git", "PSFzf", "oh-my-posh"] {
+            assert_eq!(ModuleLoading::default_for(module), ModuleLoading::Eager);
+        }
+        for module in ["Terminal-Icons", "z"] {
+            assert_eq!(ModuleLoading::default_for(module), ModuleLoading::Idle);
+        }
+    }
+}
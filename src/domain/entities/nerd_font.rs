@@ -0,0 +1,111 @@
+/// A Nerd Fonts release archive and the face name it installs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NerdFont {
+    /// Release asset name, e.g. `Meslo` for `Meslo.zip`
+    pub archive: &'static str,
+    /// Font face name to configure in the terminal
+    pub face: &'static str,
+    pub description: &'static str,
+}
+
+impl NerdFont {
+    pub const MESLO: NerdFont = NerdFont {
+        archive: "Meslo",
+        face: "MesloLGM Nerd Font",
+        description: "Apple Menlo derivative, recommended by oh-my-posh",
+    };
+
+    pub const FIRA_CODE: NerdFont = NerdFont {
+        archive: "FiraCode",
+        face: "FiraCode Nerd Font",
+        description: "Monospace with programming ligatures",
+    };
+
+    pub const JETBRAINS_MONO: NerdFont = NerdFont {
+        archive: "JetBrainsMono",
+        face: "JetBrainsMono Nerd Font",
+        description: "JetBrains IDE font with ligatures",
+    };
+
+    pub const CASKAYDIA_COVE: NerdFont = NerdFont {
+        archive: "CascadiaCode",
+        face: "CaskaydiaCove Nerd Font",
+        description: "Cascadia Code, the Windows Terminal font",
+    };
+
+    pub const HACK: NerdFont = NerdFont {
+        archive: "Hack",
+        face: "Hack Nerd Font",
+        description: "Designed for source code",
+    };
+
+    pub const SAUCE_CODE_PRO: NerdFont = NerdFont {
+        archive: "SourceCodePro",
+        face: "SauceCodePro Nerd Font",
+        description: "Adobe Source Code Pro",
+    };
+
+    pub const UBUNTU_MONO: NerdFont = NerdFont {
+        archive: "UbuntuMono",
+        face: "UbuntuMono Nerd Font",
+        description: "Ubuntu's monospace font",
+    };
+
+    pub const DEJAVU_SANS_MONO: NerdFont = NerdFont {
+        archive: "DejaVuSansMono",
+        face: "DejaVuSansM Nerd Font",
+        description: "Wide Unicode coverage",
+    };
+
+    pub const IOSEVKA: NerdFont = NerdFont {
+        archive: "Iosevka",
+        face: "Iosevka Nerd Font",
+        description: "Narrow and compact",
+    };
+
+    pub fn all() -> &'static [NerdFont] {
+        &[
+            Self::MESLO,
+            Self::FIRA_CODE,
+            Self::JETBRAINS_MONO,
+            Self::CASKAYDIA_COVE,
+            Self::HACK,
+            Self::SAUCE_CODE_PRO,
+            Self::UBUNTU_MONO,
+            Self::DEJAVU_SANS_MONO,
+            Self::IOSEVKA,
+        ]
+    }
+
+    /// Look up a font by archive or face name, ignoring case
+    pub fn find(name: &str) -> Option<NerdFont> {
+        Self::all()
+            .iter()
+            .find(|f| f.archive.eq_ignore_ascii_case(name) || f.face.eq_ignore_ascii_case(name))
+            .copied()
+    }
+}
+
+impl Default for NerdFont {
+    fn default() -> Self {
+        Self::MESLO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_by_archive_or_face() {
+        assert_eq!(
+            NerdFont::find("cascadiacode"),
+            Some(NerdFont::CASKAYDIA_COVE)
+        );
+        assert_eq!(
+            NerdFont::find("CaskaydiaCove Nerd Font"),
+            Some(NerdFont::CASKAYDIA_COVE)
+        );
+        assert_eq!(NerdFont::find("Comic Sans"), None);
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Deserialize;
+
+/// PowerShell edition a profile is generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum PowerShellEdition {
+    /// PowerShell 7 (`pwsh`)
+    #[default]
+    Core,
+    /// Windows PowerShell 5.1 (`powershell`)
+    Desktop,
+}
+
+/// Host the PowerShell profile is generated for
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PowerShellTarget {
+    pub edition: PowerShellEdition,
+    /// Newest installed PSReadLine version, `None` when unknown
+    #[serde(default, rename = "PSReadLine")]
+    pub psreadline: Option<String>,
+}
+
+impl PowerShellTarget {
+    pub fn new(edition: PowerShellEdition) -> Self {
+        Self {
+            edition,
+            psreadline: None,
+        }
+    }
+
+    pub fn with_psreadline(mut self, version: impl Into<String>) -> Self {
+        self.psreadline = Some(version.into());
+        self
+    }
+
+    /// Whether the installed PSReadLine is at least `minimum`, e.g. "2.2.0";
+    /// `None` when the installed version is unknown
+    pub fn psreadline_at_least(&self, minimum: &str) -> Option<bool> {
+        let installed = self.psreadline.as_deref()?;
+        Some(version_parts(installed) >= version_parts(minimum))
+    }
+}
+
+/// Numeric components of a `[version]` string
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compares_psreadline_versions() {
+        let target = PowerShellTarget::new(PowerShellEdition::Desktop).with_psreadline("2.0.0");
+        assert_eq!(target.psreadline_at_least("2.1.0"), Some(false));
+
+        let target = target.with_psreadline("2.10.1");
+        assert_eq!(target.psreadline_at_least("2.2.0"), Some(true));
+
+        assert_eq!(
+            PowerShellTarget::default().psreadline_at_least("2.1.0"),
+            None
+        );
+    }
+}
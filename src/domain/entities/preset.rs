@@ -1,3 +1,5 @@
+use super::PSReadLineConfig;
+
 /// Represents a pre-configured profile preset
 #[derive(Debug, Clone)]
 pub struct ProfilePreset {
@@ -5,7 +7,9 @@ pub struct ProfilePreset {
     pub description: &'static str,
     pub theme: &'static str,
     pub plugins: &'static [&'static str],
+    /// Whether the built-in shortcut groups start enabled
     pub include_aliases: bool,
+    pub psreadline: fn() -> PSReadLineConfig,
 }
 
 impl ProfilePreset {
@@ -15,6 +19,7 @@ impl ProfilePreset {
         theme: "pure",
         plugins: &["PSReadLine", "posh-git"],
         include_aliases: false,
+        psreadline: PSReadLineConfig::minimal,
     };
 
     pub const DEVELOPER: ProfilePreset = ProfilePreset {
@@ -23,6 +28,7 @@ impl ProfilePreset {
         theme: "paradox",
         plugins: &["PSReadLine", "posh-git", "Terminal-Icons", "PSFzf", "z"],
         include_aliases: true,
+        psreadline: PSReadLineConfig::standard,
     };
 
     pub const WORK: ProfilePreset = ProfilePreset {
@@ -31,6 +37,7 @@ impl ProfilePreset {
         theme: "jandedobbeleer",
         plugins: &["PSReadLine", "posh-git", "Terminal-Icons", "PSFzf"],
         include_aliases: true,
+        psreadline: PSReadLineConfig::standard,
     };
 
     pub const CUSTOM: ProfilePreset = ProfilePreset {
@@ -39,6 +46,7 @@ impl ProfilePreset {
         theme: "",
         plugins: &[],
         include_aliases: true,
+        psreadline: PSReadLineConfig::standard,
     };
 
     pub fn all() -> &'static [ProfilePreset] {
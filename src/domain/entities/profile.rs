@@ -1,10 +1,31 @@
+use std::collections::BTreeMap;
 use typed_builder::TypedBuilder;
 
+use super::{ModuleLoading, NerdFont, PSReadLineConfig, ShortcutGroup};
+
 /// Represents a PowerShell profile configuration
 #[derive(Debug, Clone, TypedBuilder)]
 pub struct ProfileConfig {
     pub theme: String,
     pub plugins: Vec<String>,
-    #[builder(default = true)]
-    pub include_aliases: bool,
+    /// Loading per plugin, overriding [`ModuleLoading::default_for`]
+    #[builder(default)]
+    pub module_loading: BTreeMap<String, ModuleLoading>,
+    /// Aliases, functions and environment settings, in profile order
+    #[builder(default = ShortcutGroup::builtin())]
+    pub groups: Vec<ShortcutGroup>,
+    #[builder(default)]
+    pub font: NerdFont,
+    #[builder(default = PSReadLineConfig::standard())]
+    pub psreadline: PSReadLineConfig,
+}
+
+impl ProfileConfig {
+    /// When the profile imports `plugin`
+    pub fn loading(&self, plugin: &str) -> ModuleLoading {
+        self.module_loading
+            .get(plugin)
+            .cloned()
+            .unwrap_or_else(|| ModuleLoading::default_for(plugin))
+    }
 }
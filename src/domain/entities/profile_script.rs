@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Shell whose startup file gets a profile next to the PowerShell one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileShell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+}
+
+/// Startup file a profile script is written to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupFile {
+    /// `$PROFILE` of the PowerShell host, replaced as a whole
+    PowerShellProfile,
+    /// File relative to the home directory, e.g. `.bashrc`; only the block
+    /// managed by this tool is replaced
+    Home(PathBuf),
+    /// File relative to the platform configuration directory (`~/.config`,
+    /// `%APPDATA%`), with a managed block like [`StartupFile::Home`]
+    Config(PathBuf),
+}
+
+/// Startup script rendered from a [`ProfileConfig`](super::ProfileConfig)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileScript {
+    pub file: StartupFile,
+    pub content: String,
+}
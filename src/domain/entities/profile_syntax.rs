@@ -0,0 +1,97 @@
+use serde::Deserialize;
+
+use super::{quote, ProfileConfig};
+
+/// Name of a `# --- Name ---` section header line of a generated profile
+pub fn section_header(line: &str) -> Option<&str> {
+    line.strip_prefix("# --- ")
+        .and_then(|rest| rest.strip_suffix(" ---"))
+}
+
+/// Parse error reported by the PowerShell language parser
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SyntaxError {
+    /// 1-based line of the start of the offending extent
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl SyntaxError {
+    /// Script parsing `profile` without running it and listing its errors
+    pub fn query(profile: &str) -> String {
+        format!(
+            "$errors = $null
+$null = [System.Management.Automation.Language.Parser]::ParseInput({}, [ref]$null, [ref]$errors)
+$errors | ForEach-Object {{
+    [pscustomobject]@{{ Line = $_.Extent.StartLineNumber; Column = $_.Extent.StartColumnNumber; Message = $_.Message }}
+}}",
+            quote(profile.trim_start_matches('\u{feff}'))
+        )
+    }
+
+    /// e.g. `line 12, column 3 in group kube: Missing closing '}'`
+    pub fn describe(&self, profile: &str, config: &ProfileConfig) -> String {
+        format!(
+            "line {}, column {} in {}: {}",
+            self.line,
+            self.column,
+            origin(section_at(profile, self.line), config),
+            self.message
+        )
+    }
+}
+
+/// Section containing the 1-based `line`
+fn section_at(profile: &str, line: usize) -> Option<&str> {
+    profile.lines().take(line).filter_map(section_header).last()
+}
+
+/// Setting the section of a generated profile comes from
+fn origin(section: Option<&str>, config: &ProfileConfig) -> String {
+    match section {
+        None => "the profile header".to_string(),
+        Some(name) if config.groups.iter().any(|group| group.name == name) => {
+            format!("group {}", name)
+        }
+        Some("PSReadLine Settings") => "the psreadline settings".to_string(),
+        Some("Import Modules" | "Deferred Modules" | "On-demand Modules") => {
+            "the plugins and module_loading settings".to_string()
+        }
+        Some(name) => format!("section {}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ShortcutGroup;
+
+    #[test]
+    fn test_maps_errors_to_sections() {
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec![])
+            .groups(vec![ShortcutGroup::new("kube")])
+            .build();
+        let profile = "# Title\n# --- PSReadLine Settings ---\nSet-PSReadLineOption\n\n# --- kube ---\nfunction k {\n";
+        let error = |line| SyntaxError {
+            line,
+            column: 12,
+            message: "Missing closing '}'".to_string(),
+        };
+
+        assert_eq!(
+            error(6).describe(profile, &config),
+            "line 6, column 12 in group kube: Missing closing '}'"
+        );
+        assert!(error(3)
+            .describe(profile, &config)
+            .contains("in the psreadline settings:"));
+        assert!(error(1)
+            .describe(profile, &config)
+            .contains("in the profile header:"));
+        assert!(SyntaxError::query("\u{feff}'it'").contains("ParseInput('''it''', [ref]$null"));
+    }
+}
@@ -0,0 +1,163 @@
+/// PowerShell single-quoted string literal
+///
+/// PowerShell also accepts typographic single quotes as delimiters, so those
+/// are doubled along with `'`.
+pub fn quote(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('\'');
+    for c in value.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            literal.push(c);
+        }
+        literal.push(c);
+    }
+    literal.push('\'');
+    literal
+}
+
+/// Command invocation whose arguments are passed as quoted literals
+///
+/// Values never become PowerShell code, so names coming from the settings file
+/// cannot inject commands. Command and parameter names are always literals in
+/// the code; anything but letters, digits, `-` and `_` panics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PsCommand {
+    script: String,
+}
+
+impl PsCommand {
+    /// Invoke a cmdlet or function by name
+    pub fn new(command: &str) -> Self {
+        assert!(is_identifier(command), "invalid command name: {}", command);
+        Self {
+            script: command.to_string(),
+        }
+    }
+
+    /// Invoke an executable by path or name with the call operator
+    pub fn native(program: &str) -> Self {
+        Self {
+            script: format!("& {}", quote(program)),
+        }
+    }
+
+    /// Positional argument
+    pub fn arg(mut self, value: impl AsRef<str>) -> Self {
+        self.script.push(' ');
+        self.script.push_str(&quote(value.as_ref()));
+        self
+    }
+
+    /// Positional arguments
+    pub fn args<I, S>(self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        values.into_iter().fold(self, PsCommand::arg)
+    }
+
+    /// Named parameter, e.g. `-Name 'posh-git'`
+    pub fn param(mut self, name: &str, value: impl AsRef<str>) -> Self {
+        assert!(is_identifier(name), "invalid parameter name: {}", name);
+        self.script
+            .push_str(&format!(" -{} {}", name, quote(value.as_ref())));
+        self
+    }
+
+    /// Array parameter, e.g. `-Name 'gs', 'gl'`
+    pub fn list<I, S>(mut self, name: &str, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        assert!(is_identifier(name), "invalid parameter name: {}", name);
+        let values: Vec<String> = values.into_iter().map(|v| quote(v.as_ref())).collect();
+        self.script
+            .push_str(&format!(" -{} {}", name, values.join(", ")));
+        self
+    }
+
+    /// Switch parameter, e.g. `-Force`
+    pub fn switch(mut self, name: &str) -> Self {
+        assert!(is_identifier(name), "invalid parameter name: {}", name);
+        self.script.push_str(&format!(" -{}", name));
+        self
+    }
+
+    /// Boolean parameter, e.g. `-HistoryNoDuplicates:$true`
+    pub fn flag(mut self, name: &str, value: bool) -> Self {
+        assert!(is_identifier(name), "invalid parameter name: {}", name);
+        self.script.push_str(&format!(" -{}:${}", name, value));
+        self
+    }
+
+    /// Pipe the output into another command, or a fixed script fragment such as
+    /// a `Where-Object` filter built with [`quote`]
+    pub fn pipe(mut self, next: impl Into<PsCommand>) -> Self {
+        self.script.push_str(" | ");
+        self.script.push_str(&next.into().script);
+        self
+    }
+
+    pub fn to_script(&self) -> String {
+        self.script.clone()
+    }
+}
+
+impl From<&str> for PsCommand {
+    /// Script fragment used verbatim; it must not contain unquoted input
+    fn from(fragment: &str) -> Self {
+        Self {
+            script: fragment.to_string(),
+        }
+    }
+}
+
+impl From<String> for PsCommand {
+    fn from(fragment: String) -> Self {
+        Self { script: fragment }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotes_arguments() {
+        assert_eq!(quote("it's"), "'it''s'");
+        assert_eq!(quote("a\u{2019}b"), "'a\u{2019}\u{2019}b'");
+        assert_eq!(quote("$(Remove-Item ~)"), "'$(Remove-Item ~)'");
+
+        let cmd = PsCommand::native("C:\\Program Files\\tool.exe")
+            .args(["--name", "x'; exit"])
+            .pipe("Out-Null");
+        assert_eq!(
+            cmd.to_script(),
+            "& 'C:\\Program Files\\tool.exe' '--name' 'x''; exit' | Out-Null"
+        );
+
+        let cmd = PsCommand::new("Get-Command").list("Name", ["gs", "it's"]);
+        assert_eq!(cmd.to_script(), "Get-Command -Name 'gs', 'it''s'");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid parameter name")]
+    fn test_rejects_parameter_name() {
+        let _ = PsCommand::new("Get-Module").switch("Force; Remove-Item ~");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid command name")]
+    fn test_rejects_command_name() {
+        let _ = PsCommand::new("Get-Module | iex");
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Key binding style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum EditMode {
+    Windows,
+    Emacs,
+    Vi,
+}
+
+/// Where inline suggestions come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PredictionSource {
+    None,
+    History,
+    Plugin,
+    HistoryAndPlugin,
+}
+
+/// How suggestions are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PredictionViewStyle {
+    InlineView,
+    ListView,
+}
+
+/// Feedback on errors and invalid keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum BellStyle {
+    None,
+    Visual,
+    Audible,
+}
+
+/// When commands are added to the history file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum HistorySaveStyle {
+    SaveIncrementally,
+    SaveAtExit,
+    SaveNothing,
+}
+
+/// Syntax element colored by PSReadLine (`Set-PSReadLineOption -Colors`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+pub enum ColorToken {
+    Command,
+    Comment,
+    ContinuationPrompt,
+    Default,
+    Emphasis,
+    Error,
+    InlinePrediction,
+    Keyword,
+    ListPrediction,
+    ListPredictionSelected,
+    ListPredictionTooltip,
+    Member,
+    Number,
+    Operator,
+    Parameter,
+    Selection,
+    String,
+    Type,
+    Variable,
+}
+
+/// What a key handler does
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyAction {
+    /// Built-in PSReadLine function, e.g. `HistorySearchBackward`
+    Function { function: String },
+    /// PowerShell script block body
+    Script { script: String },
+}
+
+/// Binding of a key or chord, e.g. `Ctrl+d` or `Ctrl+x,Ctrl+e`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct KeyHandler {
+    pub chord: String,
+    #[serde(flatten)]
+    pub action: KeyAction,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl KeyHandler {
+    pub fn function(chord: &str, function: &str) -> Self {
+        Self {
+            chord: chord.to_string(),
+            action: KeyAction::Function {
+                function: function.to_string(),
+            },
+            description: None,
+        }
+    }
+}
+
+/// PSReadLine options written to the PowerShell profile; unset options keep
+/// PSReadLine's defaults
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PSReadLineConfig {
+    pub edit_mode: Option<EditMode>,
+    pub prediction_source: Option<PredictionSource>,
+    pub prediction_view_style: Option<PredictionViewStyle>,
+    pub bell_style: Option<BellStyle>,
+    pub history_no_duplicates: Option<bool>,
+    pub history_search_cursor_moves_to_end: Option<bool>,
+    pub maximum_history_count: Option<u32>,
+    pub history_save_style: Option<HistorySaveStyle>,
+    /// Color per token: a console color name (`DarkCyan`), `#rrggbb` or an
+    /// ANSI escape sequence
+    pub colors: BTreeMap<ColorToken, String>,
+    pub key_handlers: Vec<KeyHandler>,
+}
+
+/// Names accepted by `[ConsoleColor]`
+const CONSOLE_COLORS: &[&str] = &[
+    "Black",
+    "DarkBlue",
+    "DarkGreen",
+    "DarkCyan",
+    "DarkRed",
+    "DarkMagenta",
+    "DarkYellow",
+    "Gray",
+    "DarkGray",
+    "Blue",
+    "Green",
+    "Cyan",
+    "Red",
+    "Magenta",
+    "Yellow",
+    "White",
+];
+
+impl PSReadLineConfig {
+    /// Windows key bindings with prefix history search on the arrow keys
+    pub fn minimal() -> Self {
+        Self {
+            edit_mode: Some(EditMode::Windows),
+            key_handlers: vec![
+                KeyHandler::function("UpArrow", "HistorySearchBackward"),
+                KeyHandler::function("DownArrow", "HistorySearchForward"),
+            ],
+            ..Self::default()
+        }
+    }
+
+    /// [`minimal`](Self::minimal) plus inline history predictions and syntax colors
+    pub fn standard() -> Self {
+        let colors = [
+            (ColorToken::InlinePrediction, "Cyan"),
+            (ColorToken::Command, "Yellow"),
+            (ColorToken::Parameter, "Green"),
+            (ColorToken::String, "Magenta"),
+            (ColorToken::Operator, "DarkCyan"),
+            (ColorToken::Variable, "White"),
+        ];
+        Self {
+            prediction_source: Some(PredictionSource::History),
+            prediction_view_style: Some(PredictionViewStyle::InlineView),
+            colors: colors
+                .into_iter()
+                .map(|(token, color)| (token, color.to_string()))
+                .collect(),
+            ..Self::minimal()
+        }
+    }
+
+    /// Options set in `overrides` replace these; colors and key handlers are
+    /// replaced per token and chord
+    pub fn merge(mut self, overrides: PSReadLineConfig) -> Self {
+        self.edit_mode = overrides.edit_mode.or(self.edit_mode);
+        self.prediction_source = overrides.prediction_source.or(self.prediction_source);
+        self.prediction_view_style = overrides
+            .prediction_view_style
+            .or(self.prediction_view_style);
+        self.bell_style = overrides.bell_style.or(self.bell_style);
+        self.history_no_duplicates = overrides
+            .history_no_duplicates
+            .or(self.history_no_duplicates);
+        self.history_search_cursor_moves_to_end = overrides
+            .history_search_cursor_moves_to_end
+            .or(self.history_search_cursor_moves_to_end);
+        self.maximum_history_count = overrides
+            .maximum_history_count
+            .or(self.maximum_history_count);
+        self.history_save_style = overrides.history_save_style.or(self.history_save_style);
+        self.colors.extend(overrides.colors);
+        for handler in overrides.key_handlers {
+            self.key_handlers
+                .retain(|existing| !existing.chord.eq_ignore_ascii_case(&handler.chord));
+            self.key_handlers.push(handler);
+        }
+        self
+    }
+
+    /// Reject values PSReadLine would fail on at shell startup
+    pub fn validate(&self) -> Result<()> {
+        if self.maximum_history_count == Some(0) {
+            anyhow::bail!("maximum_history_count must be at least 1");
+        }
+        if self.prediction_view_style.is_some()
+            && self.prediction_source == Some(PredictionSource::None)
+        {
+            anyhow::bail!("prediction_view_style needs a prediction_source other than None");
+        }
+        for (token, color) in &self.colors {
+            if !is_color(color) {
+                anyhow::bail!(
+                    "Invalid color {:?} for {:?}: use a console color name, #rrggbb or an escape sequence",
+                    color,
+                    token
+                );
+            }
+        }
+        for handler in &self.key_handlers {
+            if !is_chord(&handler.chord) {
+                anyhow::bail!("Invalid key chord {:?}", handler.chord);
+            }
+            match &handler.action {
+                KeyAction::Function { function }
+                    if function.is_empty()
+                        || !function.chars().all(|c| c.is_ascii_alphanumeric()) =>
+                {
+                    anyhow::bail!(
+                        "Invalid PSReadLine function {:?} for {}",
+                        function,
+                        handler.chord
+                    )
+                }
+                KeyAction::Script { script } if script.trim().is_empty() => {
+                    anyhow::bail!("Empty script for key {}", handler.chord)
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_color(color: &str) -> bool {
+    let hex = color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+    hex || color.starts_with("\u{1b}[")
+        || CONSOLE_COLORS
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(color))
+}
+
+/// Comma-separated key sequence of `+`-joined modifiers and keys, e.g. `Ctrl+x,Ctrl+e`
+fn is_chord(chord: &str) -> bool {
+    !chord.is_empty()
+        && chord.split(',').all(|keys| {
+            // `Ctrl++` binds the plus key itself
+            let keys = keys.trim().replace("++", "+Plus");
+            !keys.is_empty()
+                && keys
+                    .split('+')
+                    .all(|key| !key.is_empty() && !key.contains(char::is_whitespace))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_merges_and_validates() {
+        let overrides: PSReadLineConfig = serde_json::from_str(
+            r##"{
+                "edit_mode": "Vi",
+                "bell_style": "None",
+                "colors": { "Command": "#ff8800", "Comment": "DarkGray" },
+                "key_handlers": [
+                    { "chord": "uparrow", "function": "PreviousHistory" },
+                    { "chord": "Ctrl+x,Ctrl+e", "script": "Invoke-Item $PROFILE", "description": "Open profile" }
+                ]
+            }"##,
+        )
+        .unwrap();
+
+        let config = PSReadLineConfig::standard().merge(overrides);
+        config.validate().unwrap();
+        assert_eq!(config.edit_mode, Some(EditMode::Vi));
+        assert_eq!(config.prediction_source, Some(PredictionSource::History));
+        assert_eq!(config.colors[&ColorToken::Command], "#ff8800");
+        assert_eq!(config.colors[&ColorToken::Variable], "White");
+        assert_eq!(config.key_handlers.len(), 3);
+        assert_eq!(
+            config.key_handlers[1],
+            KeyHandler::function("uparrow", "PreviousHistory")
+        );
+
+        let mut invalid = PSReadLineConfig::minimal();
+        invalid
+            .colors
+            .insert(ColorToken::Error, "Orange".to_string());
+        assert!(invalid.validate().is_err());
+
+        let invalid = PSReadLineConfig::minimal().merge(PSReadLineConfig {
+            key_handlers: vec![KeyHandler::function("Ctrl+d", "Delete'; exit")],
+            ..Default::default()
+        });
+        assert!(invalid.validate().is_err());
+        assert!(is_chord("Ctrl++") && !is_chord("Ctrl+,x"));
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{
+    CollisionPolicy, Hook, InstallScope, ModuleBackend, ModuleLoading, PSReadLineConfig,
+    ProfileShell, ShortcutGroupOverride, ToolSpec,
+};
+
+/// User settings loaded from the configuration file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Module installation backend
+    pub module_backend: ModuleBackend,
+    /// Scope of PowerShell modules; `all_users` needs an elevated run
+    pub module_scope: InstallScope,
+    /// Minimum version required per module name
+    pub module_versions: HashMap<String, String>,
+    /// When the profile imports each module: `eager`, `idle` or `on_demand`
+    pub module_loading: HashMap<String, ModuleLoading>,
+    /// Nerd Font archive or face name, prompted for when unset
+    pub font: Option<String>,
+    /// Timeout in seconds per component name, overriding installer defaults
+    pub timeouts: HashMap<String, u64>,
+    /// Commands run before or after setup stages
+    pub hooks: Vec<Hook>,
+    /// Extra command-line tools installed from GitHub releases
+    pub tools: Vec<ToolSpec>,
+    /// Other shells whose startup files get the same theme and aliases
+    pub shells: Vec<ProfileShell>,
+    /// PSReadLine options overriding those of the selected preset
+    pub psreadline: PSReadLineConfig,
+    /// Alias, function and environment groups; a group named like a built-in
+    /// one (`listing`, `git`) toggles or extends it
+    pub groups: Vec<ShortcutGroupOverride>,
+    /// What to do with aliases and functions named like existing commands
+    pub alias_collisions: CollisionPolicy,
+}
+
+impl Settings {
+    /// Reject values that would only fail once PowerShell runs them
+    pub fn validate(&self) -> Result<()> {
+        for (module, version) in &self.module_versions {
+            // Compared as [version], which has no prerelease suffix
+            let parts: Vec<&str> = version.split('.').collect();
+            let valid = (2..=4).contains(&parts.len())
+                && parts
+                    .iter()
+                    .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+            if !valid {
+                anyhow::bail!(
+                    "Invalid minimum version {:?} for {}: use a release version such as 2.2.0",
+                    version,
+                    module
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,324 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Command an alias runs
+///
+/// Written as a plain command, or as `{ "powershell": ..., "posix": ... }` when
+/// bash, zsh, fish and Nushell need a different command than PowerShell.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "AliasSpec")]
+pub struct AliasTarget {
+    pub powershell: String,
+    pub posix: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AliasSpec {
+    Command(String),
+    PerShell {
+        powershell: String,
+        posix: Option<String>,
+    },
+}
+
+impl From<AliasSpec> for AliasTarget {
+    fn from(spec: AliasSpec) -> Self {
+        match spec {
+            AliasSpec::Command(command) => Self::new(command),
+            AliasSpec::PerShell { powershell, posix } => Self { powershell, posix },
+        }
+    }
+}
+
+impl AliasTarget {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            powershell: command.into(),
+            posix: None,
+        }
+    }
+
+    pub fn with_posix(mut self, command: impl Into<String>) -> Self {
+        self.posix = Some(command.into());
+        self
+    }
+
+    /// Command for shells other than PowerShell
+    pub fn posix(&self) -> &str {
+        self.posix.as_deref().unwrap_or(&self.powershell)
+    }
+}
+
+/// What a function runs; extra arguments are passed on
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum FunctionDef {
+    /// Native command line, e.g. `git status`; available in every shell
+    Command { command: String },
+    /// PowerShell script body
+    Body { body: String },
+    /// PowerShell script file
+    Script { script: PathBuf },
+}
+
+impl FunctionDef {
+    pub fn command(command: impl Into<String>) -> Self {
+        FunctionDef::Command {
+            command: command.into(),
+        }
+    }
+}
+
+/// Named set of aliases, functions and environment settings, switched on or
+/// off as a whole
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutGroup {
+    pub name: String,
+    pub enabled: bool,
+    pub aliases: BTreeMap<String, AliasTarget>,
+    pub functions: BTreeMap<String, FunctionDef>,
+    pub env: BTreeMap<String, String>,
+    /// Directories put in front of `PATH`
+    pub path: Vec<String>,
+}
+
+/// Group from the settings file, toggling or extending the group of the same
+/// name or defining a new one
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShortcutGroupOverride {
+    pub name: String,
+    /// Left unset, keeps the state of the overridden group; new groups are enabled
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub aliases: BTreeMap<String, AliasTarget>,
+    #[serde(default)]
+    pub functions: BTreeMap<String, FunctionDef>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub path: Vec<String>,
+}
+
+impl ShortcutGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            enabled: true,
+            aliases: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            env: BTreeMap::new(),
+            path: vec![],
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// `ll` and `la` directory listings
+    pub fn listing() -> Self {
+        let mut group = Self::new("listing");
+        group.aliases.insert(
+            "ll".to_string(),
+            AliasTarget::new("Get-ChildItem").with_posix("ls -l"),
+        );
+        group.aliases.insert(
+            "la".to_string(),
+            AliasTarget::new("Get-ChildItem -Force").with_posix("ls -la"),
+        );
+        group
+    }
+
+    /// Git shortcuts; `gcom` and `gpush` avoid PowerShell's built-in `gc` and `gp`
+    pub fn git() -> Self {
+        let mut group = Self::new("git");
+        for (name, command) in [
+            ("gs", "git status"),
+            ("gcom", "git commit"),
+            ("gpush", "git push"),
+            ("gl", "git log --oneline --graph --decorate --all"),
+            ("gco", "git checkout"),
+            ("gb", "git branch"),
+            ("gd", "git diff"),
+        ] {
+            group
+                .functions
+                .insert(name.to_string(), FunctionDef::command(command));
+        }
+        group
+    }
+
+    /// Groups shipped with the tool
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::listing(), Self::git()]
+    }
+
+    /// `groups` with those in `overrides` applied: a group with the name of an
+    /// existing one sets its `enabled` state when given and adds or replaces its
+    /// entries, other groups are appended
+    pub fn merge_all(mut groups: Vec<Self>, overrides: Vec<ShortcutGroupOverride>) -> Vec<Self> {
+        for group in overrides {
+            match groups.iter_mut().find(|g| g.name == group.name) {
+                Some(existing) => {
+                    if let Some(enabled) = group.enabled {
+                        existing.enabled = enabled;
+                    }
+                    existing.aliases.extend(group.aliases);
+                    existing.functions.extend(group.functions);
+                    existing.env.extend(group.env);
+                    existing.path.extend(group.path);
+                }
+                None => groups.push(Self {
+                    name: group.name,
+                    enabled: group.enabled.unwrap_or(true),
+                    aliases: group.aliases,
+                    functions: group.functions,
+                    env: group.env,
+                    path: group.path,
+                }),
+            }
+        }
+        groups
+    }
+
+    /// Names of the aliases and functions the group defines
+    pub fn command_names(&self) -> impl Iterator<Item = &str> {
+        self.aliases
+            .keys()
+            .chain(self.functions.keys())
+            .map(String::as_str)
+    }
+
+    /// Define the alias or function `from` as `to` instead
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if let Some(target) = self.aliases.remove(from) {
+            self.aliases.insert(to.to_string(), target);
+        }
+        if let Some(function) = self.functions.remove(from) {
+            self.functions.insert(to.to_string(), function);
+        }
+    }
+
+    /// Reject names that cannot be defined in every shell, and group names that
+    /// would break the `# --- name ---` header written above the group
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty()
+            || self.name.contains(['\n', '\r'])
+            || self.name.contains("---")
+        {
+            anyhow::bail!("Invalid group name {:?}", self.name);
+        }
+        for name in self.command_names() {
+            let valid = name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if name.is_empty() || !valid {
+                anyhow::bail!(
+                    "Invalid alias or function name {:?} in group {}",
+                    name,
+                    self.name
+                );
+            }
+        }
+        for name in self.env.keys() {
+            let valid = name
+                .chars()
+                .enumerate()
+                .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+            if name.is_empty() || !valid {
+                anyhow::bail!(
+                    "Invalid environment variable {:?} in group {}",
+                    name,
+                    self.name
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_and_merges_groups() {
+        let overrides: Vec<ShortcutGroupOverride> = serde_json::from_str(
+            r#"[
+                { "name": "git", "enabled": false },
+                { "name": "listing", "aliases": { "lt": { "powershell": "Get-ChildItem | Sort-Object LastWriteTime", "posix": "ls -lt" } } },
+                {
+                    "name": "kube",
+                    "aliases": { "k": "kubectl" },
+                    "functions": {
+                        "kctx": { "command": "kubectl config use-context" },
+                        "kwatch": { "body": "kubectl get pods -w @args" },
+                        "kdeploy": { "script": "C:\\scripts\\deploy.ps1" }
+                    },
+                    "env": { "KUBECONFIG": "C:\\kube\\config" },
+                    "path": ["C:\\kube\\bin"]
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let groups = ShortcutGroup::merge_all(ShortcutGroup::builtin(), overrides);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].aliases["lt"].posix(), "ls -lt");
+        assert_eq!(groups[0].aliases["ll"].posix(), "ls -l");
+        assert!(!groups[1].enabled);
+        assert_eq!(groups[1].functions.len(), 7);
+        assert_eq!(groups[2].aliases["k"], AliasTarget::new("kubectl"));
+        assert_eq!(
+            groups[2].functions["kdeploy"],
+            FunctionDef::Script {
+                script: PathBuf::from("C:\\scripts\\deploy.ps1")
+            }
+        );
+        for group in &groups {
+            group.validate().unwrap();
+        }
+
+        let mut invalid = ShortcutGroup::new("bad");
+        invalid
+            .functions
+            .insert("x; rm".to_string(), FunctionDef::command("ls"));
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = ShortcutGroup::new("bad");
+        invalid.env.insert("1PATH".to_string(), String::new());
+        assert!(invalid.validate().is_err());
+
+        for name in ["", "a\nb", "a --- b"] {
+            assert!(ShortcutGroup::new(name).validate().is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_override_keeps_state_unless_enabled_is_given() {
+        let overrides: Vec<ShortcutGroupOverride> = serde_json::from_str(
+            r#"[
+                { "name": "git", "functions": { "gst": { "command": "git stash" } } },
+                { "name": "listing", "enabled": true },
+                { "name": "kube", "aliases": { "k": "kubectl" } }
+            ]"#,
+        )
+        .unwrap();
+        let builtin = vec![
+            ShortcutGroup::listing().enabled(false),
+            ShortcutGroup::git().enabled(false),
+        ];
+
+        let groups = ShortcutGroup::merge_all(builtin, overrides);
+        assert!(groups[0].enabled);
+        assert!(!groups[1].enabled);
+        assert!(groups[1].functions.contains_key("gst"));
+        assert!(groups[2].enabled);
+    }
+}
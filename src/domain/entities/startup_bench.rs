@@ -0,0 +1,289 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use super::profile_syntax::section_header;
+use super::quote;
+
+/// Prefix of the output line carrying the timings of an instrumented profile
+const TIMINGS_MARKER: &str = "@@setup_powershell_bench:";
+
+/// Script started for the no-profile baseline
+pub const BASELINE_SCRIPT: &str = "exit 0";
+
+/// Options of the `bench` command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Launches measured per variant
+    pub runs: usize,
+    /// Profile to measure instead of the current `$PROFILE`
+    pub profile: Option<PathBuf>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            runs: 10,
+            profile: None,
+        }
+    }
+}
+
+impl BenchOptions {
+    /// Options from command-line arguments, `None` unless the first one is `bench`
+    pub fn from_args<I, S>(args: I) -> Result<Option<Self>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut args = args.into_iter();
+        if args.next().as_ref().map(AsRef::as_ref) != Some("bench") {
+            return Ok(None);
+        }
+
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "-n" | "--runs" => {
+                    let value = args.next().context("--runs needs a number")?;
+                    options.runs = value
+                        .as_ref()
+                        .parse()
+                        .ok()
+                        .filter(|runs| *runs > 0)
+                        .with_context(|| format!("Invalid number of runs: {}", value.as_ref()))?;
+                }
+                "--profile" => {
+                    let value = args.next().context("--profile needs a path")?;
+                    options.profile = Some(PathBuf::from(value.as_ref()));
+                }
+                other => anyhow::bail!("Unknown bench option: {}", other),
+            }
+        }
+        Ok(Some(options))
+    }
+}
+
+/// Time spent in a profile section or an `Import-Module` call
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StartupTiming {
+    pub name: String,
+    pub ms: f64,
+}
+
+#[derive(Deserialize)]
+struct Timings {
+    #[serde(default)]
+    sections: Vec<StartupTiming>,
+    #[serde(default)]
+    imports: Vec<StartupTiming>,
+}
+
+/// One launch of the shell with the instrumented profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartupSample {
+    /// Wall-clock time from process start to exit
+    pub total_ms: f64,
+    pub sections: Vec<StartupTiming>,
+    pub imports: Vec<StartupTiming>,
+}
+
+impl StartupSample {
+    /// Sample from the output of an [`instrument_profile`] script
+    pub fn parse(output: &str, total_ms: f64) -> Result<Self> {
+        let json = output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(TIMINGS_MARKER))
+            .context("The instrumented profile printed no timings")?;
+        let timings: Timings =
+            serde_json::from_str(json).context("Unexpected timings from the profile")?;
+        Ok(Self {
+            total_ms,
+            sections: timings.sections,
+            imports: timings.imports,
+        })
+    }
+}
+
+/// Profile with each `# --- Section ---` timed and every `Import-Module` call
+/// timed by a wrapper function, printing the timings as a line of JSON
+///
+/// Sections only start at top-level comment lines, which the generated
+/// profile never places inside a block.
+pub fn instrument_profile(profile: &str) -> String {
+    let mut script = String::from(
+        r#"$__bench = @{ sections = [Collections.ArrayList]::new(); imports = [Collections.ArrayList]::new() }
+function Import-Module {
+    $__start = [Diagnostics.Stopwatch]::StartNew()
+    Microsoft.PowerShell.Core\Import-Module @args
+    $__name = $args | Where-Object { $_ -is [string] -and -not $_.StartsWith('-') } | Select-Object -First 1
+    $null = $__bench.imports.Add(@{ name = "$__name"; ms = $__start.Elapsed.TotalMilliseconds })
+}
+$__section = [Diagnostics.Stopwatch]::StartNew()
+"#,
+    );
+
+    let end_section = |name: &str| {
+        format!(
+            "$null = $__bench.sections.Add(@{{ name = {}; ms = $__section.Elapsed.TotalMilliseconds }}); $__section.Restart()\n",
+            quote(name)
+        )
+    };
+    let mut section = "Header".to_string();
+    for line in profile.trim_start_matches('\u{feff}').lines() {
+        if let Some(name) = section_header(line) {
+            script += &end_section(&section);
+            section = name.to_string();
+        }
+        script += line;
+        script.push('\n');
+    }
+    script += &end_section(&section);
+    script += &format!(
+        "{} + (ConvertTo-Json -InputObject $__bench -Depth 3 -Compress)\n",
+        quote(TIMINGS_MARKER)
+    );
+    script
+}
+
+/// Median and 95th percentile in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub median: f64,
+    pub p95: f64,
+}
+
+impl Stats {
+    /// Nearest-rank statistics of `values`; zero when there are none
+    pub fn of(values: &[f64]) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let rank = |p: f64| {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            let index = (p * sorted.len() as f64).ceil() as usize;
+            sorted[index.clamp(1, sorted.len()) - 1]
+        };
+        Self {
+            median: rank(0.5),
+            p95: rank(0.95),
+        }
+    }
+}
+
+/// Startup times with and without the profile, and where the time goes
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub runs: usize,
+    pub baseline: Stats,
+    pub profile: Stats,
+    /// Per section, in profile order
+    pub sections: Vec<(String, Stats)>,
+    /// Per imported module, in import order
+    pub imports: Vec<(String, Stats)>,
+}
+
+impl BenchReport {
+    pub fn new(baseline_ms: &[f64], samples: &[StartupSample]) -> Self {
+        let totals: Vec<f64> = samples.iter().map(|s| s.total_ms).collect();
+        Self {
+            runs: samples.len(),
+            baseline: Stats::of(baseline_ms),
+            profile: Stats::of(&totals),
+            sections: breakdown(samples, |s| &s.sections),
+            imports: breakdown(samples, |s| &s.imports),
+        }
+    }
+
+    /// Median time the profile adds to a launch
+    pub fn overhead_ms(&self) -> f64 {
+        self.profile.median - self.baseline.median
+    }
+}
+
+/// Stats per timing name; a name missing from a sample counts as zero there
+fn breakdown(
+    samples: &[StartupSample],
+    timings: impl Fn(&StartupSample) -> &Vec<StartupTiming>,
+) -> Vec<(String, Stats)> {
+    let mut names: Vec<&str> = vec![];
+    for timing in samples.iter().flat_map(&timings) {
+        if !names.contains(&timing.name.as_str()) {
+            names.push(&timing.name);
+        }
+    }
+    names
+        .into_iter()
+        .map(|name| {
+            let values: Vec<f64> = samples
+                .iter()
+                .map(|sample| {
+                    timings(sample)
+                        .iter()
+                        .filter(|timing| timing.name == name)
+                        .map(|timing| timing.ms)
+                        .sum()
+                })
+                .collect();
+            (name.to_string(), Stats::of(&values))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruments_and_summarizes() {
+        let script = instrument_profile(
+            "\u{feff}# Title\r\n# --- Import Modules ---\r\nImport-Module 'PSReadLine'\r\n# --- git ---\r\nfunction gs { git status @args }\r\n",
+        );
+        assert!(script.contains(
+            "$null = $__bench.sections.Add(@{ name = 'Header'; ms = $__section.Elapsed.TotalMilliseconds }); $__section.Restart()\n# --- Import Modules ---\n"
+        ));
+        assert!(script.contains("name = 'git'; ms"));
+        assert!(script.ends_with(
+            "'@@setup_powershell_bench:' + (ConvertTo-Json -InputObject $__bench -Depth 3 -Compress)\n"
+        ));
+
+        let output = r#"PSReadLine warning
+@@setup_powershell_bench:{"sections":[{"name":"Import Modules","ms":40.0}],"imports":[{"name":"PSReadLine","ms":35.5}]}"#;
+        let fast = StartupSample::parse(output, 300.0).unwrap();
+        let slow = StartupSample {
+            total_ms: 500.0,
+            sections: vec![],
+            imports: vec![],
+        };
+        assert!(StartupSample::parse("", 1.0).is_err());
+
+        let report = BenchReport::new(&[100.0, 120.0, 110.0], &[fast, slow]);
+        assert_eq!(
+            report.baseline,
+            Stats {
+                median: 110.0,
+                p95: 120.0
+            }
+        );
+        assert_eq!(report.profile.median, 300.0);
+        assert_eq!(report.overhead_ms(), 190.0);
+        assert_eq!(report.imports[0].0, "PSReadLine");
+        assert_eq!(
+            report.imports[0].1,
+            Stats {
+                median: 0.0,
+                p95: 35.5
+            }
+        );
+
+        let options = BenchOptions::from_args(["bench", "-n", "5", "--profile", "p.ps1"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.runs, 5);
+        assert_eq!(options.profile, Some(PathBuf::from("p.ps1")));
+        assert_eq!(BenchOptions::from_args(["-v"]).unwrap(), None);
+        assert!(BenchOptions::from_args(["bench", "-n", "0"]).is_err());
+        assert!(BenchOptions::from_args(["bench", "--run", "5"]).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::ArchiveKind;
+
+/// Command-line tool installed from GitHub releases, defined in the settings file
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolSpec {
+    /// Component name, defaults to `binary`
+    pub name: Option<String>,
+    /// GitHub `owner/repo`
+    pub repository: String,
+    /// Release tag to install, latest when unset
+    pub version: Option<String>,
+    /// Asset name glob keyed by `<os>-<arch>` (e.g. `windows-x86_64`) or `<os>`
+    pub assets: HashMap<String, String>,
+    /// Archive format, inferred from the asset name when unset
+    pub archive: Option<ArchiveKind>,
+    /// Executable to expose, without the `.exe` suffix
+    pub binary: String,
+    /// Arguments printing the installed version
+    #[serde(default = "default_version_args")]
+    pub version_args: Vec<String>,
+}
+
+fn default_version_args() -> Vec<String> {
+    vec!["--version".to_string()]
+}
+
+impl ToolSpec {
+    pub fn component_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.binary)
+    }
+
+    /// Asset pattern for the given platform, preferring `<os>-<arch>` over `<os>`
+    pub fn asset_pattern(&self, os: &str, arch: &str) -> Option<&str> {
+        self.assets
+            .get(&format!("{}-{}", os, arch))
+            .or_else(|| self.assets.get(os))
+            .map(String::as_str)
+    }
+    /// Reject a `binary` that is not a plain file name: it is joined to the bin
+    /// directory and used as an archive glob
+    pub fn validate(&self) -> Result<()> {
+        let valid = !self.binary.is_empty()
+            && self.binary != "."
+            && self.binary != ".."
+            && !self
+                .binary
+                .contains(['/', '\\', ':', '*', '?', '[', ']', '{', '}', '!']);
+        if !valid {
+            anyhow::bail!(
+                "Invalid binary {:?} for tool {}: use a plain file name",
+                self.binary,
+                self.repository
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(binary: &str) -> ToolSpec {
+        serde_json::from_value(serde_json::json!({
+            "repository": "BurntSushi/ripgrep",
+            "assets": { "linux": "*linux*" },
+            "binary": binary,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validates_binary_name() {
+        tool("rg").validate().unwrap();
+        tool("git-delta").validate().unwrap();
+        for binary in [
+            "", "..", "bin/rg", "..\\rg", "C:rg", "r*", "rg?", "[rg]", "{a,b}",
+        ] {
+            assert!(tool(binary).validate().is_err(), "{:?}", binary);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+/// Amount of console output, chosen with `-q`, `-v` and `-vv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Warnings, errors and the final summary only
+    Quiet,
+    /// Progress of each step
+    #[default]
+    Normal,
+    /// Also the commands being run and step timings
+    Verbose,
+    /// Also the output of every command
+    Trace,
+}
+
+impl Verbosity {
+    /// Verbosity from command-line flags; repeated or combined `-v` flags add up
+    pub fn from_args<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut level = 1i32;
+        for arg in args {
+            match arg.as_ref() {
+                "-q" | "--quiet" => level = 0,
+                "--verbose" => level += 1,
+                flag if flag.len() > 1
+                    && flag.starts_with('-')
+                    && flag[1..].bytes().all(|b| b == b'v') =>
+                {
+                    level += flag.len() as i32 - 1
+                }
+                _ => {}
+            }
+        }
+
+        match level {
+            ..=0 => Self::Quiet,
+            1 => Self::Normal,
+            2 => Self::Verbose,
+            _ => Self::Trace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_args() {
+        assert_eq!(Verbosity::from_args(["setup"]), Verbosity::Normal);
+        assert_eq!(Verbosity::from_args(["setup", "-q"]), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_args(["setup", "-v"]), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_args(["setup", "-vv"]), Verbosity::Trace);
+        assert_eq!(
+            Verbosity::from_args(["setup", "-v", "--verbose"]),
+            Verbosity::Trace
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors that interrupt a setup step
+#[derive(Debug, Error)]
+pub enum SetupError {
+    #[error("Setup cancelled")]
+    Cancelled,
+
+    #[error("{component} timed out after {}s", .timeout.as_secs())]
+    TimedOut {
+        component: String,
+        timeout: Duration,
+    },
+
+    #[error("Hook '{hook}' failed: {reason}")]
+    HookFailed { hook: String, reason: String },
+
+    #[error("The generated {shell} profile is not valid and was not written:\n  {}", .errors.join("\n  "))]
+    InvalidProfile { shell: String, errors: Vec<String> },
+}
+
+/// Installer failure with a known cause and a suggested fix
+#[derive(Debug, Error)]
+#[error("{message}\nHint: {hint}")]
+pub struct ActionableError {
+    pub message: String,
+    pub hint: String,
+}
+
+/// Error record raised by a PowerShell command
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[error("{message} ({error_id})")]
+pub struct PowerShellError {
+    pub message: String,
+    /// `CategoryInfo.Category`, e.g. `ObjectNotFound`
+    pub category: String,
+    /// `FullyQualifiedErrorId`, e.g. `NoMatchFoundForCriteria,Microsoft.PowerShell...`
+    pub error_id: String,
+    #[serde(default)]
+    pub target: String,
+    pub exception_type: String,
+}
@@ -0,0 +1,18 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use crate::domain::{ArchiveKind, ExtractOptions};
+
+/// Abstraction for unpacking downloaded archives
+#[async_trait]
+pub trait Extractor: Send + Sync {
+    /// Extract the selected entries of `archive` into `dest`, returning the written files
+    async fn extract(
+        &self,
+        archive: &Path,
+        kind: ArchiveKind,
+        dest: &Path,
+        options: &ExtractOptions,
+    ) -> Result<Vec<PathBuf>>;
+}
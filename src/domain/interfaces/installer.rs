@@ -1,15 +1,31 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::domain::{InstallOutcome, InstallScope};
+
+/// Time an installer may run before it is considered hung
+pub const DEFAULT_INSTALL_TIMEOUT: Duration = Duration::from_secs(600);
 
 /// Abstraction for installing components
 #[async_trait]
 pub trait Installer: Send + Sync {
     /// Install the component
-    async fn install(&self) -> Result<()>;
+    async fn install(&self) -> Result<InstallOutcome>;
 
     /// Check if the component is already installed
     async fn is_installed(&self) -> bool;
 
     /// Get the component name
     fn component_name(&self) -> &str;
+
+    /// Maximum duration of a detection or install step
+    fn timeout(&self) -> Duration {
+        DEFAULT_INSTALL_TIMEOUT
+    }
+
+    /// Where the component is installed; `AllUsers` steps need elevation
+    fn scope(&self) -> InstallScope {
+        InstallScope::CurrentUser
+    }
 }
@@ -1,11 +1,15 @@
 pub mod downloader;
+pub mod extractor;
 pub mod installer;
+pub mod profile_renderer;
 pub mod profile_writer;
 pub mod shell;
 pub mod terminal_config;
 
 pub use downloader::Downloader;
-pub use installer::Installer;
+pub use extractor::Extractor;
+pub use installer::{Installer, DEFAULT_INSTALL_TIMEOUT};
+pub use profile_renderer::ProfileRenderer;
 pub use profile_writer::ProfileWriter;
-pub use shell::ShellRunner;
+pub use shell::{ShellQuery, ShellRunner};
 pub use terminal_config::TerminalConfigurator;
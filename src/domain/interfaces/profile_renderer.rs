@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+
+use crate::domain::interfaces::ShellRunner;
+use crate::domain::{ProfileConfig, ProfileScript};
+
+/// Translates a profile configuration into the startup scripts of one shell
+#[async_trait]
+pub trait ProfileRenderer: Send + Sync {
+    /// Shell the scripts are for, e.g. "bash"
+    fn shell_name(&self) -> &str;
+
+    /// Inspect the installed shell before rendering; runs after the modules
+    /// are installed so that versions they bring along are seen
+    async fn detect(&self, _shell: &dyn ShellRunner) {}
+
+    /// Startup scripts applying the theme, plugins and aliases of `config`
+    fn render(&self, config: &ProfileConfig) -> Vec<ProfileScript>;
+
+    /// Parts of `config` the shell cannot express, reported to the user
+    /// instead of being dropped silently
+    fn unsupported(&self, _config: &ProfileConfig) -> Vec<String> {
+        vec![]
+    }
+}
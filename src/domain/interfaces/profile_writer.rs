@@ -2,12 +2,25 @@ use anyhow::Result;
 use async_trait::async_trait;
 use std::path::Path;
 
+use crate::domain::entities::splice_managed_block;
+
 /// Abstraction for writing profile files
 #[async_trait]
 pub trait ProfileWriter: Send + Sync {
     /// Write profile content to the specified path
     async fn write(&self, path: &Path, content: &str) -> Result<()>;
 
+    /// Current content of a startup file, empty when it does not exist yet
+    async fn read(&self, path: &Path) -> Result<String>;
+
+    /// Replace the block managed by this tool in a startup file shared with
+    /// the user, keeping everything outside it
+    async fn write_block(&self, path: &Path, content: &str) -> Result<()> {
+        let existing = self.read(path).await?;
+        self.write(path, &splice_managed_block(&existing, content))
+            .await
+    }
+
     /// Get the profile path for the current shell
     async fn get_profile_path(&self) -> Result<std::path::PathBuf>;
 }
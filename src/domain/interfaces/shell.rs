@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 
 /// Abstraction for executing shell commands
 #[async_trait]
@@ -7,9 +8,29 @@ pub trait ShellRunner: Send + Sync {
     /// Execute a shell command and return its output
     async fn run(&self, cmd: &str) -> Result<String>;
 
+    /// Execute a command and return its output objects as a JSON array
+    ///
+    /// Errors raised by the command surface as [`PowerShellError`](crate::domain::PowerShellError).
+    async fn query(&self, cmd: &str) -> Result<serde_json::Value>;
+
     /// Check if a command exists in the system
     async fn exists(&self, command: &str) -> bool;
 
     /// Get the shell name (e.g., "pwsh", "powershell")
     fn shell_name(&self) -> &str;
 }
+
+/// Typed queries on top of [`ShellRunner::query`]
+#[async_trait]
+pub trait ShellQuery {
+    /// Execute a command and deserialize each output object
+    async fn query_as<T: DeserializeOwned>(&self, cmd: &str) -> Result<Vec<T>>;
+}
+
+#[async_trait]
+impl<S: ShellRunner + ?Sized> ShellQuery for S {
+    async fn query_as<T: DeserializeOwned>(&self, cmd: &str) -> Result<Vec<T>> {
+        let value = self.query(cmd).await?;
+        serde_json::from_value(value).with_context(|| format!("Unexpected output from: {}", cmd))
+    }
+}
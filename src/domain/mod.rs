@@ -1,5 +1,19 @@
 pub mod entities;
+pub mod errors;
 pub mod interfaces;
 
-pub use entities::{ProfileConfig, ProfilePreset};
-pub use interfaces::{Downloader, Installer, ProfileWriter, ShellRunner, TerminalConfigurator};
+pub use entities::{
+    quote, splice_managed_block, AliasTarget, ArchiveKind, BellStyle, BenchOptions, BenchReport,
+    CollisionPolicy, ColorToken, EditMode, ExistingCommand, ExtractEvent, ExtractOptions,
+    ExtractProgress, FailurePolicy, FunctionDef, HistorySaveStyle, Hook, HookCommand, HookStage,
+    InstallOutcome, InstallScope, KeyAction, KeyHandler, ModuleBackend, ModuleInfo, ModuleLoading,
+    NerdFont, PSReadLineConfig, PowerShellEdition, PowerShellTarget, PredictionSource,
+    PredictionViewStyle, ProfileConfig, ProfilePreset, ProfileScript, ProfileShell, PsCommand,
+    Settings, ShortcutGroup, ShortcutGroupOverride, StartupFile, StartupSample, StartupTiming,
+    Stats, SyntaxError, ToolSpec, Verbosity,
+};
+pub use errors::{ActionableError, PowerShellError, SetupError};
+pub use interfaces::{
+    Downloader, Extractor, Installer, ProfileRenderer, ProfileWriter, ShellQuery, ShellRunner,
+    TerminalConfigurator,
+};
@@ -0,0 +1,390 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use crate::domain::interfaces::Extractor;
+use crate::domain::{ArchiveKind, ExtractEvent, ExtractOptions};
+
+/// Archive extractor for zip, tar.gz and tar.xz files
+pub struct ArchiveExtractor;
+
+impl ArchiveExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ArchiveExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Extractor for ArchiveExtractor {
+    async fn extract(
+        &self,
+        archive: &Path,
+        kind: ArchiveKind,
+        dest: &Path,
+        options: &ExtractOptions,
+    ) -> Result<Vec<PathBuf>> {
+        let job = Extraction::new(dest, options)?;
+        let archive = archive.to_path_buf();
+        tokio::task::spawn_blocking(move || job.run(&archive, kind)).await?
+    }
+}
+
+/// Entry path inside the destination, rejecting absolute paths and `..`
+fn sanitize(entry: &Path) -> Result<PathBuf> {
+    let mut clean = PathBuf::new();
+    for component in entry.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => anyhow::bail!("Refusing to extract unsafe path {}", entry.display()),
+        }
+    }
+    Ok(clean)
+}
+
+/// Whether a symlink at `entry` pointing to `target` stays inside the destination
+#[cfg_attr(not(unix), allow(dead_code))]
+fn link_stays_inside(entry: &Path, target: &Path) -> bool {
+    let mut depth = entry.components().count() as isize - 1;
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => depth -= 1,
+            _ => return false,
+        }
+        if depth < 0 {
+            return false;
+        }
+    }
+    true
+}
+
+/// A single extraction run, executed on a blocking thread
+struct Extraction {
+    dest: PathBuf,
+    include: Option<GlobSet>,
+    options: ExtractOptions,
+    written: Vec<PathBuf>,
+}
+
+impl Extraction {
+    fn new(dest: &Path, options: &ExtractOptions) -> Result<Self> {
+        let include = if options.include.is_empty() {
+            None
+        } else {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &options.include {
+                builder.add(
+                    Glob::new(pattern)
+                        .with_context(|| format!("Invalid extract pattern: {}", pattern))?,
+                );
+            }
+            Some(builder.build()?)
+        };
+
+        Ok(Self {
+            dest: dest.to_path_buf(),
+            include,
+            options: options.clone(),
+            written: vec![],
+        })
+    }
+
+    fn run(mut self, archive: &Path, kind: ArchiveKind) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(&self.dest)
+            .with_context(|| format!("Failed to create {}", self.dest.display()))?;
+        let file =
+            File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+
+        match kind {
+            ArchiveKind::Zip => self.zip(file)?,
+            ArchiveKind::TarGz => self.tar(flate2::read::GzDecoder::new(file))?,
+            ArchiveKind::TarXz => self.tar(lzma_rust2::XzReader::new(file, true))?,
+            ArchiveKind::Binary => {
+                let name = archive.file_name().context("Archive has no file name")?;
+                self.file(Path::new(name), &mut { file }, None)?;
+            }
+        }
+
+        Ok(self.written)
+    }
+
+    fn zip(&mut self, file: File) -> Result<()> {
+        let mut zip = zip::ZipArchive::new(file).context("Failed to read zip archive")?;
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i)?;
+            let path = sanitize(Path::new(entry.name()))?;
+            if entry.is_dir() {
+                self.dir(&path)?;
+            } else if !entry.is_symlink() {
+                let mode = entry.unix_mode();
+                self.file(&path, &mut entry, mode)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn tar(&mut self, reader: impl Read) -> Result<()> {
+        let mut tar = tar::Archive::new(reader);
+        for entry in tar.entries().context("Failed to read tar archive")? {
+            let mut entry = entry?;
+            let path = sanitize(&entry.path()?)?;
+            let header = entry.header();
+            let mode = header.mode().ok();
+
+            match header.entry_type() {
+                tar::EntryType::Directory => self.dir(&path)?,
+                tar::EntryType::Regular | tar::EntryType::Continuous => {
+                    self.file(&path, &mut entry, mode)?
+                }
+                tar::EntryType::Symlink => {
+                    if let Some(target) = entry.link_name()? {
+                        self.symlink(&path, &target)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn selected(&self, path: &Path) -> bool {
+        let selected = self.include.as_ref().is_none_or(|set| set.is_match(path));
+        if !selected {
+            self.emit(ExtractEvent::Skipped {
+                entry: path.to_path_buf(),
+            });
+        }
+        selected
+    }
+
+    fn target(&self, path: &Path) -> Option<PathBuf> {
+        if self.options.flatten {
+            path.file_name().map(|name| self.dest.join(name))
+        } else {
+            Some(self.dest.join(path))
+        }
+    }
+
+    fn dir(&self, path: &Path) -> Result<()> {
+        if !self.options.flatten && self.selected(path) {
+            self.create_dirs(&self.dest.join(path))?;
+        }
+        Ok(())
+    }
+
+    /// Create the directories above `target`
+    fn create_parents(&self, target: &Path) -> Result<()> {
+        match target.parent() {
+            Some(parent) => self.create_dirs(parent),
+            None => Ok(()),
+        }
+    }
+
+    /// Create `dir` inside the destination, refusing to go through a symlink
+    /// an earlier entry created: links are checked lexically when extracted,
+    /// but a chain of them can still lead out of the destination
+    fn create_dirs(&self, dir: &Path) -> Result<()> {
+        let relative = dir.strip_prefix(&self.dest)?;
+        let mut current = self.dest.clone();
+        for component in relative.components() {
+            current.push(component);
+            match std::fs::symlink_metadata(&current) {
+                Ok(metadata) if metadata.file_type().is_symlink() => anyhow::bail!(
+                    "Refusing to extract {} through the link {}",
+                    relative.display(),
+                    current.display()
+                ),
+                Ok(_) => {}
+                Err(_) => std::fs::create_dir(&current)
+                    .with_context(|| format!("Failed to create {}", current.display()))?,
+            }
+        }
+
+        let dest = std::fs::canonicalize(&self.dest)?;
+        let parent = std::fs::canonicalize(current)?;
+        if !parent.starts_with(&dest) {
+            anyhow::bail!(
+                "Refusing to extract {} outside the destination",
+                relative.display()
+            );
+        }
+        Ok(())
+    }
+
+    fn file(&mut self, path: &Path, reader: &mut impl Read, mode: Option<u32>) -> Result<()> {
+        if !self.selected(path) {
+            return Ok(());
+        }
+        let Some(target) = self.target(path) else {
+            return Ok(());
+        };
+        self.create_parents(&target)?;
+        // Never write through a link left by an earlier entry
+        if std::fs::symlink_metadata(&target).is_ok_and(|m| m.file_type().is_symlink()) {
+            std::fs::remove_file(&target)?;
+        }
+
+        let mut out = File::create(&target)
+            .with_context(|| format!("Failed to create {}", target.display()))?;
+        let bytes = std::io::copy(reader, &mut out)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = mode {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&target, std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+
+        self.emit(ExtractEvent::Extracted {
+            path: target.clone(),
+            bytes,
+        });
+        self.written.push(target);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn symlink(&mut self, path: &Path, target: &Path) -> Result<()> {
+        if self.options.flatten || !self.selected(path) {
+            return Ok(());
+        }
+        if !link_stays_inside(path, target) {
+            anyhow::bail!(
+                "Refusing to extract link {} pointing outside the destination",
+                path.display()
+            );
+        }
+
+        let link = self.dest.join(path);
+        self.create_parents(&link)?;
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(target, &link)?;
+        self.written.push(link);
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn symlink(&mut self, _path: &Path, _target: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    fn emit(&self, event: ExtractEvent) {
+        if let Some(progress) = &self.options.progress {
+            progress(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_with(path: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = tar::Header::new_gnu();
+        header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+        header.set_size(data.len() as u64);
+        header.set_mode(0o755);
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_cksum();
+
+        let mut builder = tar::Builder::new(vec![]);
+        builder.append(&header, data).unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_rejects_path_traversal() {
+        let dest = std::env::temp_dir().join("setup_powershell_extract_traversal");
+        let mut job = Extraction::new(&dest, &ExtractOptions::default()).unwrap();
+        let result = job.tar(&tar_with("../evil.sh", b"echo pwned")[..]);
+
+        assert!(result.is_err());
+        assert!(!dest.parent().unwrap().join("evil.sh").exists());
+    }
+
+    #[test]
+    fn test_selects_by_glob_and_flattens() {
+        let dest = std::env::temp_dir().join("setup_powershell_extract_glob");
+        let _ = std::fs::remove_dir_all(&dest);
+        let options = ExtractOptions::builder()
+            .include(vec!["**/bin/tool".to_string()])
+            .flatten(true)
+            .build();
+        let mut job = Extraction::new(&dest, &options).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+
+        job.tar(&tar_with("tool-1.0/bin/tool", b"binary")[..])
+            .unwrap();
+        job.tar(&tar_with("tool-1.0/README.md", b"docs")[..])
+            .unwrap();
+
+        assert_eq!(job.written, vec![dest.join("tool")]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dest.join("tool"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+        std::fs::remove_dir_all(&dest).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_chained_symlinks() {
+        let root = std::env::temp_dir().join("setup_powershell_extract_chain");
+        let _ = std::fs::remove_dir_all(&root);
+        let dest = root.join("dest");
+
+        let mut builder = tar::Builder::new(vec![]);
+        for (path, target) in [("a", "."), ("a/b", "..")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            builder.append_link(&mut header, path, target).unwrap();
+        }
+        let mut header = tar::Header::new_gnu();
+        header.set_size(6);
+        header.set_mode(0o4755);
+        builder
+            .append_data(&mut header, "a/b/x", &b"pwned!"[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let mut job = Extraction::new(&dest, &ExtractOptions::default()).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+        assert!(job.tar(&archive[..]).is_err());
+
+        assert!(!root.join("x").exists());
+        assert!(!root.join("b").exists());
+        assert!(std::fs::read_dir(&root).unwrap().count() == 1);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_link_stays_inside() {
+        assert!(link_stays_inside(Path::new("a/b/link"), Path::new("../c")));
+        assert!(!link_stays_inside(
+            Path::new("a/link"),
+            Path::new("../../etc/passwd")
+        ));
+        assert!(!link_stays_inside(
+            Path::new("link"),
+            Path::new("/etc/passwd")
+        ));
+    }
+}
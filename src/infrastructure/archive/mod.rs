@@ -0,0 +1,3 @@
+pub mod archive_extractor;
+
+pub use archive_extractor::ArchiveExtractor;
@@ -0,0 +1,3 @@
+pub mod settings_file;
+
+pub use settings_file::SettingsFile;
@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::domain::Settings;
+
+/// Environment variable overriding the settings file location
+const CONFIG_ENV: &str = "SETUP_POWERSHELL_CONFIG";
+
+/// JSON settings file loader
+pub struct SettingsFile {
+    path: Option<PathBuf>,
+}
+
+impl SettingsFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Settings file from `SETUP_POWERSHELL_CONFIG` or the user config directory
+    pub fn discover() -> Self {
+        let path = std::env::var_os(CONFIG_ENV).map(PathBuf::from).or_else(|| {
+            dirs::config_dir().map(|dir| dir.join("setup_powershell").join("config.json"))
+        });
+        Self { path }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Load settings, falling back to defaults when the file does not exist
+    pub async fn load(&self) -> Result<Settings> {
+        let Some(path) = &self.path else {
+            return Ok(Settings::default());
+        };
+
+        if !path.exists() {
+            return Ok(Settings::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        let settings: Settings = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        settings
+            .validate()
+            .with_context(|| format!("Invalid settings in {}", path.display()))?;
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{InstallScope, ModuleBackend};
+
+    #[tokio::test]
+    async fn test_missing_file_uses_defaults() {
+        let settings = SettingsFile::new("does-not-exist.json")
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(settings.module_backend, ModuleBackend::Auto);
+        assert_eq!(settings.module_scope, InstallScope::CurrentUser);
+    }
+
+    #[test]
+    fn test_parse_module_backend() {
+        let settings: Settings = serde_json::from_str(
+            r#"{ "module_backend": "psresourceget", "module_scope": "all_users" }"#,
+        )
+        .unwrap();
+        assert_eq!(settings.module_backend, ModuleBackend::PSResourceGet);
+        assert_eq!(settings.module_scope, InstallScope::AllUsers);
+    }
+
+    #[test]
+    fn test_rejects_prerelease_minimum_version() {
+        let settings: Settings =
+            serde_json::from_str(r#"{ "module_versions": { "PSReadLine": "2.4.0-beta1" } }"#)
+                .unwrap();
+        assert!(settings.validate().is_err());
+
+        let settings: Settings =
+            serde_json::from_str(r#"{ "module_versions": { "PSReadLine": "2.2.0" } }"#).unwrap();
+        settings.validate().unwrap();
+    }
+}
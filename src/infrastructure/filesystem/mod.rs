@@ -1,3 +1,5 @@
 pub mod profile_fs_writer;
+pub mod temp_file;
 
 pub use profile_fs_writer::ProfileFsWriter;
+pub use temp_file::TempFile;
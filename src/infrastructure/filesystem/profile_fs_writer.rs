@@ -4,7 +4,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
 
+use super::TempFile;
 use crate::domain::interfaces::{ProfileWriter, ShellRunner};
+use crate::domain::splice_managed_block;
 
 /// File system-based profile writer
 pub struct ProfileFsWriter {
@@ -15,25 +17,75 @@ impl ProfileFsWriter {
     pub fn new(shell: Arc<dyn ShellRunner>) -> Self {
         Self { shell }
     }
-}
 
-#[async_trait]
-impl ProfileWriter for ProfileFsWriter {
-    async fn write(&self, path: &Path, content: &str) -> Result<()> {
-        // Ensure parent directory exists
+    /// Write `content` next to `path`, then move it into place, so an interrupted
+    /// write never truncates the existing file
+    ///
+    /// A symlinked file is replaced at its target, keeping the link, and the
+    /// permissions of an existing file are kept.
+    async fn replace(path: &Path, content: &str) -> Result<()> {
+        let resolved = Self::resolve(path).await?;
+        let path = resolved.as_path();
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)
                 .await
                 .context("Failed to create profile directory")?;
         }
 
-        fs::write(path, content.trim())
+        let mut tmp_name = path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp = TempFile::new(tmp_name);
+
+        fs::write(tmp.path(), content)
             .await
             .context("Failed to write profile")?;
+        if let Ok(metadata) = fs::metadata(path).await {
+            fs::set_permissions(tmp.path(), metadata.permissions())
+                .await
+                .context("Failed to keep profile permissions")?;
+        }
+        fs::rename(tmp.path(), path)
+            .await
+            .context("Failed to replace profile")?;
+        tmp.persist();
 
         Ok(())
     }
 
+    /// File a write to `path` should replace: the target of a symlink, even a
+    /// dangling one, or `path` itself
+    async fn resolve(path: &Path) -> Result<PathBuf> {
+        match fs::canonicalize(path).await {
+            Ok(target) => Ok(target),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => match fs::read_link(path).await {
+                Ok(target) => Ok(path.parent().unwrap_or(Path::new("")).join(target)),
+                Err(_) => Ok(path.to_path_buf()),
+            },
+            Err(e) => Err(e).with_context(|| format!("Failed to resolve {}", path.display())),
+        }
+    }
+}
+
+#[async_trait]
+impl ProfileWriter for ProfileFsWriter {
+    async fn write(&self, path: &Path, content: &str) -> Result<()> {
+        Self::replace(path, content.trim()).await
+    }
+
+    async fn read(&self, path: &Path) -> Result<String> {
+        match fs::read_to_string(path).await {
+            Ok(existing) => Ok(existing),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Like the default, without trimming the spliced file's final newline
+    async fn write_block(&self, path: &Path, content: &str) -> Result<()> {
+        let existing = self.read(path).await?;
+        Self::replace(path, &splice_managed_block(&existing, content)).await
+    }
+
     async fn get_profile_path(&self) -> Result<PathBuf> {
         let output = self.shell.run("$PROFILE").await?;
         let path_str = output.trim();
@@ -44,12 +96,64 @@ impl ProfileWriter for ProfileFsWriter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::infrastructure::shell::PowerShellRunner;
+    use crate::infrastructure::shell::FakeShell;
 
     #[tokio::test]
     async fn test_profile_writer_creation() {
-        let shell = Arc::new(PowerShellRunner::pwsh());
+        let shell = Arc::new(FakeShell::new());
         let writer = ProfileFsWriter::new(shell);
         assert!(std::mem::size_of_val(&writer) > 0);
     }
+
+    #[tokio::test]
+    async fn test_write_block_keeps_user_lines() {
+        let path = std::env::temp_dir().join("setup_powershell_bashrc");
+        std::fs::write(&path, "export EDITOR=vim\n").unwrap();
+        let writer = ProfileFsWriter::new(Arc::new(FakeShell::new()));
+
+        writer.write_block(&path, "alias ll='ls -l'").await.unwrap();
+        writer.write_block(&path, "alias la='ls -A'").await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(content.starts_with("export EDITOR=vim\n"));
+        assert!(content.contains("alias la='ls -A'"));
+        assert!(!content.contains("alias ll"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_write_block_follows_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join("setup_powershell_dotfiles");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("bashrc");
+        let link = dir.join(".bashrc");
+        std::fs::write(&target, "export EDITOR=vim\n").unwrap();
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o600)).unwrap();
+        std::os::unix::fs::symlink("bashrc", &link).unwrap();
+
+        let writer = ProfileFsWriter::new(Arc::new(FakeShell::new()));
+        writer.write_block(&link, "alias ll='ls -l'").await.unwrap();
+
+        let is_link = std::fs::symlink_metadata(&link).unwrap().is_symlink();
+        let content = std::fs::read_to_string(&target).unwrap();
+        let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(is_link);
+        assert!(content.contains("alias ll='ls -l'"));
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[tokio::test]
+    async fn test_profile_path_comes_from_shell() {
+        let shell = Arc::new(FakeShell::new().on_run("$PROFILE", "/home/me/profile.ps1\n"));
+        let writer = ProfileFsWriter::new(shell);
+        assert_eq!(
+            writer.get_profile_path().await.unwrap(),
+            PathBuf::from("/home/me/profile.ps1")
+        );
+    }
 }
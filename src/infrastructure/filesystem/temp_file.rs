@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+
+/// File removed when dropped, so interrupted steps leave no partial files behind
+pub struct TempFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            keep: false,
+        }
+    }
+
+    /// File with the given name in the system temp directory
+    pub fn in_temp_dir(name: &str) -> Self {
+        Self::new(std::env::temp_dir().join(name))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keep the file on drop and return its path
+    pub fn persist(mut self) -> PathBuf {
+        self.keep = true;
+        std::mem::take(&mut self.path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_removed_unless_persisted() {
+        let dropped = TempFile::in_temp_dir("setup_powershell_temp_file_dropped");
+        std::fs::write(dropped.path(), "partial").unwrap();
+        let dropped_path = dropped.path().to_path_buf();
+        drop(dropped);
+        assert!(!dropped_path.exists());
+
+        let kept = TempFile::in_temp_dir("setup_powershell_temp_file_kept");
+        std::fs::write(kept.path(), "complete").unwrap();
+        let kept_path = kept.persist();
+        assert!(kept_path.exists());
+        std::fs::remove_file(kept_path).unwrap();
+    }
+}
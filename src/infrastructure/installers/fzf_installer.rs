@@ -1,17 +1,28 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use async_trait::async_trait;
-use colored::Colorize;
 use std::process::Stdio;
+use std::sync::Arc;
 use tokio::process::Command;
+use tracing::{debug, info};
 
 use crate::domain::interfaces::Installer;
+use crate::domain::InstallOutcome;
+use crate::infrastructure::process::{run_winget, RunLog, WINGET_FLAGS};
 
 /// fzf installer using winget (required for PSFzf module)
-pub struct FzfInstaller;
+pub struct FzfInstaller {
+    log: Option<Arc<RunLog>>,
+}
 
 impl FzfInstaller {
     pub fn new() -> Self {
-        Self
+        Self { log: None }
+    }
+
+    /// Record installer output in the run log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.log = Some(log);
+        self
     }
 }
 
@@ -23,32 +34,21 @@ impl Default for FzfInstaller {
 
 #[async_trait]
 impl Installer for FzfInstaller {
-    async fn install(&self) -> Result<()> {
-        println!(
-            "{} {}",
-            "➡".blue(),
-            "winget install fzf -s winget".bright_black()
-        );
+    async fn install(&self) -> Result<InstallOutcome> {
+        debug!("➡ winget install fzf -s winget");
 
-        let status = Command::new("winget")
-            .args([
-                "install",
-                "fzf",
-                "-s",
-                "winget",
-                "--accept-source-agreements",
-            ])
-            .status()
-            .await
-            .context("Failed to execute winget")?;
+        let mut command = Command::new("winget");
+        command
+            .kill_on_drop(true)
+            .args(["install", "fzf", "-s", "winget"])
+            .args(WINGET_FLAGS);
+        let outcome = run_winget(&mut command, "winget install fzf", self.log.as_deref()).await?;
 
-        if !status.success() {
-            anyhow::bail!("winget install fzf failed");
+        if outcome != InstallOutcome::AlreadyPresent {
+            info!("✅ fzf installed");
         }
 
-        println!("{} {}", "✅".green(), "fzf installed".green());
-
-        Ok(())
+        Ok(outcome)
     }
 
     async fn is_installed(&self) -> bool {
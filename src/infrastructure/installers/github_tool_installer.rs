@@ -0,0 +1,407 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+use crate::domain::interfaces::{Downloader, Extractor, Installer};
+use crate::domain::{ArchiveKind, ExtractOptions, InstallOutcome, ToolSpec};
+use crate::infrastructure::filesystem::TempFile;
+use crate::infrastructure::process::{run_captured, RunLog};
+
+const GITHUB_API: &str = "https://api.github.com/repos";
+
+/// Installer for a command-line tool published as GitHub release assets
+pub struct GithubToolInstaller {
+    spec: ToolSpec,
+    downloader: Arc<dyn Downloader>,
+    extractor: Arc<dyn Extractor>,
+    log: Option<Arc<RunLog>>,
+    bin_dir: Option<PathBuf>,
+}
+
+impl GithubToolInstaller {
+    pub fn new(
+        spec: ToolSpec,
+        downloader: Arc<dyn Downloader>,
+        extractor: Arc<dyn Extractor>,
+    ) -> Self {
+        Self {
+            spec,
+            downloader,
+            extractor,
+            log: None,
+            bin_dir: Self::bin_dir(),
+        }
+    }
+
+    /// Record version probe output in the run log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    /// Install into `dir` instead of the per-user bin directory
+    pub fn with_bin_dir(mut self, dir: PathBuf) -> Self {
+        self.bin_dir = Some(dir);
+        self
+    }
+
+    /// Per-user directory receiving tool binaries; `~/.local/bin` where the
+    /// platform has no user bin directory, like macOS
+    pub fn bin_dir() -> Option<PathBuf> {
+        if cfg!(windows) {
+            dirs::data_local_dir()
+                .map(|dir| dir.join("Programs").join("setup_powershell").join("bin"))
+        } else {
+            dirs::executable_dir()
+                .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("bin")))
+        }
+    }
+
+    fn binary_file_name(&self) -> String {
+        if cfg!(windows) {
+            format!("{}.exe", self.spec.binary)
+        } else {
+            self.spec.binary.clone()
+        }
+    }
+
+    /// Release description from the GitHub API, fetched with the downloader
+    async fn fetch_release(&self) -> Result<serde_json::Value> {
+        let url = match &self.spec.version {
+            Some(tag) => format!(
+                "{}/{}/releases/tags/{}",
+                GITHUB_API, self.spec.repository, tag
+            ),
+            None => format!("{}/{}/releases/latest", GITHUB_API, self.spec.repository),
+        };
+        let file = TempFile::in_temp_dir(&format!("{}.release.json", self.binary_file_name()));
+        self.downloader
+            .download(&url, file.path())
+            .await
+            .with_context(|| format!("Failed to query {}", url))?;
+        let content = tokio::fs::read_to_string(file.path()).await?;
+        serde_json::from_str(&content).context("Failed to parse GitHub release")
+    }
+
+    /// Name and download URL of the release asset matching the pattern for `os-arch`
+    fn find_asset(
+        &self,
+        release: &serde_json::Value,
+        os: &str,
+        arch: &str,
+    ) -> Result<(String, String)> {
+        let pattern = self
+            .spec
+            .asset_pattern(os, arch)
+            .with_context(|| format!("No asset pattern for {}-{}", os, arch))?;
+        let matcher = globset::Glob::new(pattern)
+            .with_context(|| format!("Invalid asset pattern: {}", pattern))?
+            .compile_matcher();
+
+        release["assets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|asset| {
+                Some((
+                    asset["name"].as_str()?.to_string(),
+                    asset["browser_download_url"].as_str()?.to_string(),
+                ))
+            })
+            .find(|(name, _)| matcher.is_match(name))
+            .with_context(|| {
+                format!(
+                    "No asset matching '{}' in {} release",
+                    pattern, self.spec.repository
+                )
+            })
+    }
+
+    /// Run the version probe, preferring the copy in the user bin directory
+    async fn probe_version(&self) -> Option<String> {
+        let local = self
+            .bin_dir
+            .as_ref()
+            .map(|dir| dir.join(self.binary_file_name()))
+            .filter(|path| path.exists());
+        let program = local.unwrap_or_else(|| PathBuf::from(&self.spec.binary));
+
+        let mut command = Command::new(program);
+        command
+            .kill_on_drop(true)
+            .args(&self.spec.version_args)
+            .stdin(Stdio::null());
+        let description = format!("{} {}", self.spec.binary, self.spec.version_args.join(" "));
+        let output = run_captured(&mut command, &description, self.log.as_deref())
+            .await
+            .ok()?;
+
+        parse_version(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// First version-looking token, e.g. `14.1.0` in `ripgrep 14.1.0 (rev 1234)`
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.'))
+        .map(|token| {
+            token
+                .trim_end_matches(|c: char| !c.is_ascii_alphanumeric())
+                .to_string()
+        })
+}
+
+#[async_trait]
+impl Installer for GithubToolInstaller {
+    async fn install(&self) -> Result<InstallOutcome> {
+        let bin_dir = self
+            .bin_dir
+            .clone()
+            .context("Could not determine user bin directory")?;
+        let release = self.fetch_release().await?;
+        let (asset_name, url) =
+            self.find_asset(&release, std::env::consts::OS, std::env::consts::ARCH)?;
+        let kind = self
+            .spec
+            .archive
+            .unwrap_or_else(|| ArchiveKind::from_file_name(&asset_name));
+
+        info!("⬇ Downloading {} ...", asset_name);
+
+        let asset = TempFile::in_temp_dir(&asset_name);
+        self.downloader.download(&url, asset.path()).await?;
+
+        tokio::fs::create_dir_all(&bin_dir)
+            .await
+            .context("Failed to create bin directory")?;
+
+        let file_name = self.binary_file_name();
+        let binary = if kind == ArchiveKind::Binary {
+            let binary = bin_dir.join(&file_name);
+            tokio::fs::copy(asset.path(), &binary)
+                .await
+                .with_context(|| format!("Failed to copy {}", asset_name))?;
+            binary
+        } else {
+            let options = ExtractOptions::builder()
+                .include(vec![format!("**/{}", file_name)])
+                .flatten(true)
+                .build();
+            self.extractor
+                .extract(asset.path(), kind, &bin_dir, &options)
+                .await?
+                .into_iter()
+                .next()
+                .with_context(|| format!("{} not found in {}", file_name, asset_name))?
+        };
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).await?;
+        }
+
+        let on_path = std::env::var_os("PATH")
+            .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir == bin_dir));
+        if !on_path {
+            warn!(
+                "Add {} to your PATH to use {}",
+                bin_dir.display(),
+                self.spec.binary
+            );
+        }
+
+        let version = self.probe_version().await.unwrap_or_default();
+        info!("✅ {} {} installed", self.component_name(), version);
+
+        Ok(InstallOutcome::Installed)
+    }
+
+    async fn is_installed(&self) -> bool {
+        match (self.probe_version().await, &self.spec.version) {
+            (Some(installed), Some(wanted)) => installed == wanted.trim_start_matches('v'),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    fn component_name(&self) -> &str {
+        self.spec.component_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    /// Serves `release` for API URLs and a stub archive for anything else
+    struct FakeDownloader {
+        release: serde_json::Value,
+        urls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Downloader for FakeDownloader {
+        async fn download(&self, url: &str, path: &Path) -> Result<()> {
+            self.urls.lock().unwrap().push(url.to_string());
+            let content = if url.starts_with(GITHUB_API) {
+                self.release.to_string()
+            } else {
+                "archive".to_string()
+            };
+            Ok(tokio::fs::write(path, content).await?)
+        }
+    }
+
+    /// Writes one file per include pattern, named after its last component
+    struct FakeExtractor;
+
+    #[async_trait]
+    impl Extractor for FakeExtractor {
+        async fn extract(
+            &self,
+            _archive: &Path,
+            kind: ArchiveKind,
+            dest: &Path,
+            options: &ExtractOptions,
+        ) -> Result<Vec<PathBuf>> {
+            assert_eq!(kind, ArchiveKind::TarGz);
+            assert!(options.flatten);
+            let mut files = vec![];
+            for pattern in &options.include {
+                let file = dest.join(pattern.trim_start_matches("**/"));
+                tokio::fs::write(&file, "binary").await?;
+                files.push(file);
+            }
+            Ok(files)
+        }
+    }
+
+    fn spec() -> ToolSpec {
+        serde_json::from_value(serde_json::json!({
+            "repository": "owner/tool",
+            "assets": {
+                "linux-x86_64": "tool-*-x86_64-unknown-linux-musl.tar.gz",
+                "linux-aarch64": "tool-*-aarch64-unknown-linux-gnu.tar.gz",
+                "macos": "tool-*-apple-darwin.tar.gz",
+                "windows-x86_64": "tool-*-x86_64-pc-windows-msvc.tar.gz",
+            },
+            "binary": "tool",
+        }))
+        .unwrap()
+    }
+
+    fn release() -> serde_json::Value {
+        let assets: Vec<serde_json::Value> = [
+            "tool-1.2.0-x86_64-unknown-linux-musl.tar.gz.sha256",
+            "tool-1.2.0-x86_64-unknown-linux-musl.tar.gz",
+            "tool-1.2.0-aarch64-unknown-linux-gnu.tar.gz",
+            "tool-1.2.0-universal-apple-darwin.tar.gz",
+            "tool-1.2.0-x86_64-pc-windows-msvc.tar.gz",
+        ]
+        .into_iter()
+        .map(|name| {
+            serde_json::json!({
+                "name": name,
+                "browser_download_url": format!("https://example.com/{}", name),
+            })
+        })
+        .collect();
+        serde_json::json!({ "tag_name": "v1.2.0", "assets": assets })
+    }
+
+    fn installer(release: serde_json::Value) -> (GithubToolInstaller, Arc<FakeDownloader>) {
+        let downloader = Arc::new(FakeDownloader {
+            release,
+            urls: Mutex::new(vec![]),
+        });
+        let installer =
+            GithubToolInstaller::new(spec(), downloader.clone(), Arc::new(FakeExtractor));
+        (installer, downloader)
+    }
+
+    #[test]
+    fn test_finds_asset_per_platform() {
+        let (installer, _) = installer(release());
+        let release = release();
+        for (os, arch, asset) in [
+            (
+                "linux",
+                "x86_64",
+                "tool-1.2.0-x86_64-unknown-linux-musl.tar.gz",
+            ),
+            (
+                "linux",
+                "aarch64",
+                "tool-1.2.0-aarch64-unknown-linux-gnu.tar.gz",
+            ),
+            (
+                "macos",
+                "aarch64",
+                "tool-1.2.0-universal-apple-darwin.tar.gz",
+            ),
+            (
+                "windows",
+                "x86_64",
+                "tool-1.2.0-x86_64-pc-windows-msvc.tar.gz",
+            ),
+        ] {
+            let (name, url) = installer.find_asset(&release, os, arch).unwrap();
+            assert_eq!(name, asset);
+            assert_eq!(url, format!("https://example.com/{}", asset));
+        }
+        assert!(installer
+            .find_asset(&release, "windows", "aarch64")
+            .is_err());
+        assert!(installer
+            .find_asset(&serde_json::json!({ "assets": [] }), "linux", "x86_64")
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_installs_binary_from_release() {
+        let bin_dir = std::env::temp_dir().join("setup_powershell_github_tool");
+        let _ = std::fs::remove_dir_all(&bin_dir);
+        let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+        let (installer, downloader) = installer(release());
+        let installer = installer.with_bin_dir(bin_dir.clone());
+        let Ok((asset, url)) = installer.find_asset(&release(), os, arch) else {
+            // No fixture asset for this platform
+            return;
+        };
+
+        let outcome = installer.install().await.unwrap();
+
+        assert_eq!(outcome, InstallOutcome::Installed);
+        assert_eq!(
+            *downloader.urls.lock().unwrap(),
+            [format!("{}/owner/tool/releases/latest", GITHUB_API), url]
+        );
+        let binary = bin_dir.join(installer.binary_file_name());
+        assert_eq!(std::fs::read_to_string(&binary).unwrap(), "binary");
+        assert!(!std::env::temp_dir().join(asset).exists());
+        std::fs::remove_dir_all(&bin_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("zoxide v0.9.4\n").as_deref(), Some("0.9.4"));
+        assert_eq!(
+            parse_version("ripgrep 14.1.0 (rev e50df40a19)\n").as_deref(),
+            Some("14.1.0")
+        );
+        assert_eq!(
+            parse_version("gh version 2.40.1 (2023-12-13)\n").as_deref(),
+            Some("2.40.1")
+        );
+        assert_eq!(parse_version("no version here"), None);
+    }
+}
@@ -1,11 +1,15 @@
-pub mod font_installer;
 pub mod fzf_installer;
+pub mod github_tool_installer;
 pub mod module_installer;
+pub mod nerd_font_installer;
 pub mod oh_my_posh_installer;
+pub mod package_provider_installer;
 pub mod pwsh_installer;
 
-pub use font_installer::FontInstaller;
 pub use fzf_installer::FzfInstaller;
+pub use github_tool_installer::GithubToolInstaller;
 pub use module_installer::ModuleInstaller;
+pub use nerd_font_installer::NerdFontInstaller;
 pub use oh_my_posh_installer::OhMyPoshInstaller;
+pub use package_provider_installer::PackageProviderInstaller;
 pub use pwsh_installer::PwshInstaller;
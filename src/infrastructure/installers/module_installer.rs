@@ -1,13 +1,23 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use colored::Colorize;
 use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
 
-use crate::domain::interfaces::{Installer, ShellRunner};
+use crate::domain::interfaces::{Installer, ShellQuery, ShellRunner};
+use crate::domain::{quote, InstallOutcome, InstallScope, ModuleBackend, ModuleInfo, PsCommand};
+
+/// Projection of `Get-Module` results onto [`ModuleInfo`]
+const MODULE_INFO_SELECT: &str = "Select-Object Name, \
+    @{ Name = 'Version'; Expression = { \"$($_.Version)\" } }, \
+    @{ Name = 'Path'; Expression = { if ($_.Path) { $_.Path } else { $_.InstalledLocation } } }";
 
 /// PowerShell module installer
 pub struct ModuleInstaller {
     module_name: String,
+    minimum_version: Option<String>,
+    backend: ModuleBackend,
+    scope: InstallScope,
     shell: Arc<dyn ShellRunner>,
 }
 
@@ -15,51 +25,217 @@ impl ModuleInstaller {
     pub fn new(module_name: impl Into<String>, shell: Arc<dyn ShellRunner>) -> Self {
         Self {
             module_name: module_name.into(),
+            minimum_version: None,
+            backend: ModuleBackend::Auto,
+            scope: InstallScope::CurrentUser,
             shell,
         }
     }
+
+    /// Use a specific backend instead of detecting one
+    pub fn with_backend(mut self, backend: ModuleBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Install for all users instead of the current user (needs elevation)
+    pub fn with_scope(mut self, scope: InstallScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Require at least the given module version
+    pub fn with_minimum_version(mut self, version: impl Into<String>) -> Self {
+        self.minimum_version = Some(version.into());
+        self
+    }
+
+    /// Detect the preferred backend: PSResourceGet when present, PowerShellGet otherwise
+    pub async fn detect_backend(shell: &dyn ShellRunner) -> ModuleBackend {
+        let cmd = "[bool](Get-Command Install-PSResource -ErrorAction SilentlyContinue)";
+        match shell.run(cmd).await {
+            Ok(output) if output.trim().eq_ignore_ascii_case("true") => {
+                ModuleBackend::PSResourceGet
+            }
+            _ => ModuleBackend::PowerShellGet,
+        }
+    }
+
+    async fn resolve_backend(&self) -> ModuleBackend {
+        match self.backend {
+            ModuleBackend::Auto => Self::detect_backend(self.shell.as_ref()).await,
+            backend => backend,
+        }
+    }
+
+    fn install_command(&self, backend: ModuleBackend) -> PsCommand {
+        let scope = self.scope.as_powershell();
+        match backend {
+            ModuleBackend::PSResourceGet => {
+                let cmd = PsCommand::new("Install-PSResource")
+                    .param("Name", &self.module_name)
+                    .param("Scope", scope)
+                    .switch("TrustRepository")
+                    .switch("AcceptLicense")
+                    .switch("Quiet");
+                match &self.minimum_version {
+                    Some(version) => cmd.param("Version", format!("[{},)", version)),
+                    None => cmd,
+                }
+            }
+            _ => {
+                let cmd = PsCommand::new("Install-Module")
+                    .param("Name", &self.module_name)
+                    .switch("Force")
+                    .param("Scope", scope)
+                    .switch("AllowClobber");
+                match &self.minimum_version {
+                    Some(version) => cmd.param("MinimumVersion", version),
+                    None => cmd,
+                }
+            }
+        }
+    }
+
+    /// Installed copies of the module with either backend; the backend's own
+    /// listing (`Get-InstalledPSResource`) misses modules shipped in `$PSHOME`
+    fn detect_command(&self) -> PsCommand {
+        let cmd = PsCommand::new("Get-Module")
+            .switch("ListAvailable")
+            .param("Name", &self.module_name);
+        match &self.minimum_version {
+            Some(version) => cmd.pipe(format!(
+                "Where-Object {{ $_.Version -ge [version]{} }}",
+                quote(version)
+            )),
+            None => cmd,
+        }
+    }
 }
 
 #[async_trait]
 impl Installer for ModuleInstaller {
-    async fn install(&self) -> Result<()> {
-        let cmd = format!(
-            "Install-Module {} -Force -Scope CurrentUser -AllowClobber",
-            self.module_name
-        );
-        self.shell.run(&cmd).await?;
-        println!(
-            "{} {} {}",
-            "✅".green(),
-            self.module_name.bright_white(),
-            "installed".green()
-        );
-        Ok(())
+    async fn install(&self) -> Result<InstallOutcome> {
+        let backend = self.resolve_backend().await;
+        self.shell
+            .run(&self.install_command(backend).to_script())
+            .await?;
+        info!("✅ {} installed", self.module_name);
+        Ok(InstallOutcome::Installed)
     }
 
     async fn is_installed(&self) -> bool {
-        let cmd = format!("Get-Module -ListAvailable -Name {}", self.module_name);
-        if let Ok(output) = self.shell.run(&cmd).await {
-            !output.trim().is_empty()
-        } else {
-            false
+        let query = self.detect_command().pipe(MODULE_INFO_SELECT);
+        match self.shell.query_as::<ModuleInfo>(&query.to_script()).await {
+            Ok(modules) => !modules.is_empty(),
+            Err(_) => false,
         }
     }
 
     fn component_name(&self) -> &str {
         &self.module_name
     }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn scope(&self) -> InstallScope {
+        self.scope
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::infrastructure::shell::PowerShellRunner;
+    use crate::infrastructure::shell::FakeShell;
 
     #[tokio::test]
     async fn test_component_name() {
-        let shell = Arc::new(PowerShellRunner::pwsh());
+        let shell = Arc::new(FakeShell::new());
         let installer = ModuleInstaller::new("TestModule", shell);
         assert_eq!(installer.component_name(), "TestModule");
     }
+
+    #[test]
+    fn test_minimum_version_per_backend() {
+        let shell = Arc::new(FakeShell::new());
+        let installer = ModuleInstaller::new("posh-git", shell).with_minimum_version("1.1.0");
+
+        assert!(installer
+            .install_command(ModuleBackend::PowerShellGet)
+            .to_script()
+            .ends_with("-MinimumVersion '1.1.0'"));
+        assert!(installer
+            .install_command(ModuleBackend::PSResourceGet)
+            .to_script()
+            .ends_with("-Version '[1.1.0,)'"));
+        assert!(installer
+            .detect_command()
+            .to_script()
+            .ends_with("$_.Version -ge [version]'1.1.0' }"));
+    }
+
+    #[test]
+    fn test_scope_per_backend() {
+        let shell = Arc::new(FakeShell::new());
+        let installer = ModuleInstaller::new("z", shell);
+        assert!(installer
+            .install_command(ModuleBackend::PowerShellGet)
+            .to_script()
+            .contains("-Scope 'CurrentUser'"));
+
+        let installer = installer.with_scope(InstallScope::AllUsers);
+        for backend in [ModuleBackend::PowerShellGet, ModuleBackend::PSResourceGet] {
+            assert!(installer
+                .install_command(backend)
+                .to_script()
+                .contains("-Scope 'AllUsers'"));
+        }
+    }
+
+    #[test]
+    fn test_module_name_is_quoted() {
+        let shell = Arc::new(FakeShell::new());
+        let installer = ModuleInstaller::new("x'; Remove-Item -Recurse ~; '", shell);
+
+        for backend in [ModuleBackend::PowerShellGet, ModuleBackend::PSResourceGet] {
+            assert!(installer
+                .install_command(backend)
+                .to_script()
+                .contains("-Name 'x''; Remove-Item -Recurse ~; '''"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_installs_through_detected_backend() {
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_run("Get-Command Install-PSResource", "True")
+                .on_query("Get-Module -ListAvailable", serde_json::json!([]))
+                .on_run("Install-PSResource", ""),
+        );
+        let installer = ModuleInstaller::new("Terminal-Icons", shell.clone());
+
+        assert!(!installer.is_installed().await);
+        installer.install().await.unwrap();
+        assert!(shell
+            .calls()
+            .last()
+            .unwrap()
+            .starts_with("Install-PSResource -Name 'Terminal-Icons'"));
+    }
+
+    #[tokio::test]
+    async fn test_is_installed_reads_module_info() {
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_run("Get-Command Install-PSResource", "False")
+                .on_query(
+                    "Get-Module -ListAvailable -Name 'z'",
+                    serde_json::json!([{ "Name": "z", "Version": "1.9.4", "Path": null }]),
+                ),
+        );
+        assert!(ModuleInstaller::new("z", shell).is_installed().await);
+    }
 }
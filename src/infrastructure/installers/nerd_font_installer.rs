@@ -0,0 +1,375 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::process::Command;
+use tracing::{debug, info};
+
+use crate::domain::interfaces::{Downloader, Extractor, Installer, ShellRunner};
+use crate::domain::{quote, ArchiveKind, ExtractOptions, InstallOutcome, NerdFont};
+use crate::infrastructure::filesystem::TempFile;
+use crate::infrastructure::process::{run_captured, RunLog};
+
+const NERD_FONTS_RELEASES: &str =
+    "https://github.com/ryanoasis/nerd-fonts/releases/latest/download";
+
+/// Registry key holding per-user font registrations on Windows
+const WINDOWS_FONTS_KEY: &str = r"HKCU\Software\Microsoft\Windows NT\CurrentVersion\Fonts";
+/// Registry key holding machine-wide font registrations on Windows
+const WINDOWS_MACHINE_FONTS_KEY: &str = r"HKLM\Software\Microsoft\Windows NT\CurrentVersion\Fonts";
+
+/// First word after the family in faces of its other Nerd Font variants
+const VARIANT_WORDS: [&str; 2] = ["Mono", "Propo"];
+
+/// Nerd Font installer downloading release archives directly
+pub struct NerdFontInstaller {
+    archive: String,
+    family: String,
+    styles: Vec<String>,
+    downloader: Arc<dyn Downloader>,
+    extractor: Arc<dyn Extractor>,
+    /// Loads the fonts into the running Windows session
+    shell: Option<Arc<dyn ShellRunner>>,
+    log: Option<Arc<RunLog>>,
+}
+
+impl NerdFontInstaller {
+    /// `archive` is the release asset name (e.g. `Meslo`), `family` the installed face name
+    pub fn new(
+        archive: impl Into<String>,
+        family: impl Into<String>,
+        downloader: Arc<dyn Downloader>,
+        extractor: Arc<dyn Extractor>,
+    ) -> Self {
+        Self {
+            archive: archive.into(),
+            family: family.into(),
+            styles: ["Regular", "Bold", "Italic", "BoldItalic"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            downloader,
+            extractor,
+            shell: None,
+            log: None,
+        }
+    }
+
+    /// Installer for a font from the Nerd Fonts catalog
+    pub fn from_font(
+        font: NerdFont,
+        downloader: Arc<dyn Downloader>,
+        extractor: Arc<dyn Extractor>,
+    ) -> Self {
+        Self::new(font.archive, font.face, downloader, extractor)
+    }
+
+    pub fn meslo(downloader: Arc<dyn Downloader>, extractor: Arc<dyn Extractor>) -> Self {
+        Self::from_font(NerdFont::MESLO, downloader, extractor)
+    }
+
+    /// Record `reg` and `fc-cache` output in the run log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    /// Load the fonts into the running Windows session through `shell`, so
+    /// that the terminal can use them before the next logon
+    pub fn with_shell(mut self, shell: Arc<dyn ShellRunner>) -> Self {
+        self.shell = Some(shell);
+        self
+    }
+
+    /// Restrict extraction to the given styles (e.g. `Regular`, `BoldItalic`)
+    pub fn with_styles(mut self, styles: Vec<String>) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Per-user font directory for the current platform
+    fn fonts_dir() -> Option<PathBuf> {
+        if cfg!(windows) {
+            std::env::var("LOCALAPPDATA")
+                .map(|p| PathBuf::from(p).join("Microsoft\\Windows\\Fonts"))
+                .ok()
+        } else {
+            dirs::font_dir()
+        }
+    }
+
+    /// Glob selecting the family's font files in the archive
+    ///
+    /// Nerd Font files are named `<Family without spaces>-<Style>.ttf`,
+    /// e.g. `MesloLGMNerdFont-BoldItalic.ttf`.
+    fn include_pattern(&self) -> String {
+        format!(
+            "**/{}-{{{}}}.{{ttf,otf}}",
+            self.family.replace(' ', ""),
+            self.styles.join(",")
+        )
+    }
+
+    /// Make the installed files visible to the system font machinery
+    async fn register(&self, files: &[PathBuf], fonts_dir: &Path) -> Result<()> {
+        if cfg!(windows) {
+            for file in files {
+                let value = self.registry_value(file);
+                let mut command = Command::new("reg");
+                command
+                    .kill_on_drop(true)
+                    .args(["add", WINDOWS_FONTS_KEY, "/v", &value, "/t", "REG_SZ", "/d"])
+                    .arg(file)
+                    .arg("/f");
+                let description = format!("reg add {}", value);
+                run_captured(&mut command, &description, self.log.as_deref()).await?;
+            }
+            match &self.shell {
+                Some(shell) => Self::load(shell.as_ref(), files).await?,
+                None => debug!("Fonts become available after the next logon"),
+            }
+        } else if Command::new("fc-cache")
+            .arg("--version")
+            .output()
+            .await
+            .is_ok()
+        {
+            let mut command = Command::new("fc-cache");
+            command.kill_on_drop(true).arg("-f").arg(fonts_dir);
+            run_captured(&mut command, "fc-cache", self.log.as_deref()).await?;
+        } else {
+            // e.g. macOS, which picks up ~/Library/Fonts by itself
+            debug!("fc-cache not found, skipping the font cache refresh");
+        }
+
+        Ok(())
+    }
+
+    /// Registry value naming a font file, e.g. `MesloLGM Nerd Font Bold (TrueType)`
+    fn registry_value(&self, file: &Path) -> String {
+        let style = file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.rsplit_once('-'))
+            .map(|(_, style)| style)
+            .unwrap_or("Regular");
+        let is_otf = file
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("otf"));
+        let kind = if is_otf { "OpenType" } else { "TrueType" };
+        format!("{} {} ({})", self.family, style, kind)
+    }
+
+    /// Add the fonts to the session with `AddFontResourceW` and tell running
+    /// applications with `WM_FONTCHANGE`
+    async fn load(shell: &dyn ShellRunner, files: &[PathBuf]) -> Result<()> {
+        let files: Vec<String> = files
+            .iter()
+            .map(|file| quote(&file.to_string_lossy()))
+            .collect();
+        let script = format!(
+            r#"if (-not ('SetupPowerShell.Fonts' -as [type])) {{
+    Add-Type -Namespace SetupPowerShell -Name Fonts -MemberDefinition @'
+[DllImport("gdi32.dll", CharSet = CharSet.Unicode)]
+public static extern int AddFontResourceW(string file);
+[DllImport("user32.dll", CharSet = CharSet.Unicode)]
+public static extern IntPtr SendMessageTimeoutW(IntPtr hWnd, uint msg, UIntPtr wParam, IntPtr lParam, uint flags, uint timeout, out UIntPtr result);
+'@
+}}
+foreach ($file in @({files})) {{
+    if ([SetupPowerShell.Fonts]::AddFontResourceW($file) -eq 0) {{ throw "AddFontResource failed for $file" }}
+}}
+$result = [UIntPtr]::Zero
+# HWND_BROADCAST, WM_FONTCHANGE, SMTO_ABORTIFHUNG
+$null = [SetupPowerShell.Fonts]::SendMessageTimeoutW([IntPtr]0xffff, 0x1D, [UIntPtr]::Zero, [IntPtr]::Zero, 2, 1000, [ref]$result)"#,
+            files = files.join(", ")
+        );
+        shell
+            .run(&script)
+            .await
+            .context("Failed to load the fonts into the session")?;
+        Ok(())
+    }
+
+    /// Whether a line of `fc-list : family` or `reg query` output names this family
+    ///
+    /// fc-list prints comma-separated family names (`MesloLGM Nerd Font,MesloLGM NF`),
+    /// the registry lists faces such as `MesloLGM Nerd Font Bold Italic (TrueType)    REG_SZ    ...`.
+    fn matches_family(&self, line: &str) -> bool {
+        let line = line.trim();
+        if let Some((value, _)) = line.split_once("REG_SZ") {
+            let face = value
+                .trim()
+                .rsplit_once(" (")
+                .map_or(value.trim(), |(face, _)| face);
+            return self.matches_face(face);
+        }
+
+        line.split(',').any(|family| family.trim() == self.family)
+    }
+
+    /// Whether a registry face is this family, alone or followed by a style
+    fn matches_face(&self, face: &str) -> bool {
+        match face.strip_prefix(self.family.as_str()) {
+            Some("") => true,
+            Some(rest) => rest.strip_prefix(' ').is_some_and(|style| {
+                !style
+                    .split_whitespace()
+                    .next()
+                    .is_some_and(|word| VARIANT_WORDS.contains(&word))
+            }),
+            None => false,
+        }
+    }
+
+    /// Installed font family names reported by the platform
+    ///
+    /// On Windows both the per-user and the machine-wide registrations count.
+    async fn installed_families() -> Option<String> {
+        if !cfg!(windows) {
+            let output = Command::new("fc-list").args([":", "family"]).output().await;
+            return output
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned());
+        }
+
+        let mut families = String::new();
+        for key in [WINDOWS_FONTS_KEY, WINDOWS_MACHINE_FONTS_KEY] {
+            let output = Command::new("reg").args(["query", key]).output().await;
+            // A hive without the key lists nothing
+            if let Some(output) = output.ok().filter(|o| o.status.success()) {
+                families += &String::from_utf8_lossy(&output.stdout);
+            }
+        }
+        Some(families)
+    }
+}
+
+#[async_trait]
+impl Installer for NerdFontInstaller {
+    async fn install(&self) -> Result<InstallOutcome> {
+        let fonts_dir = Self::fonts_dir().context("Could not determine user font directory")?;
+        let archive_name = format!("{}.zip", self.archive);
+        let url = format!("{}/{}", NERD_FONTS_RELEASES, archive_name);
+        let archive = TempFile::in_temp_dir(&archive_name);
+
+        info!("⬇ Downloading {} ...", archive_name);
+
+        self.downloader.download(&url, archive.path()).await?;
+
+        let options = ExtractOptions::builder()
+            .include(vec![self.include_pattern()])
+            .flatten(true)
+            .build();
+        let files = self
+            .extractor
+            .extract(archive.path(), ArchiveKind::Zip, &fonts_dir, &options)
+            .await?;
+        drop(archive);
+
+        if files.is_empty() {
+            anyhow::bail!(
+                "No {} files matching {} found in {}",
+                self.family,
+                self.styles.join(", "),
+                archive_name
+            );
+        }
+
+        self.register(&files, &fonts_dir).await?;
+
+        info!("✅ {} installed ({} files)", self.family, files.len());
+
+        Ok(InstallOutcome::Installed)
+    }
+
+    async fn is_installed(&self) -> bool {
+        match Self::installed_families().await {
+            Some(families) => families.lines().any(|line| self.matches_family(line)),
+            None => false,
+        }
+    }
+
+    fn component_name(&self) -> &str {
+        &self.family
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::archive::ArchiveExtractor;
+    use crate::infrastructure::network::HttpDownloader;
+    use crate::infrastructure::FakeShell;
+
+    fn meslo() -> NerdFontInstaller {
+        NerdFontInstaller::meslo(
+            Arc::new(HttpDownloader::new()),
+            Arc::new(ArchiveExtractor::new()),
+        )
+    }
+
+    #[test]
+    fn test_selects_family_and_styles() {
+        let installer = meslo().with_styles(vec!["Regular".to_string(), "Bold".to_string()]);
+        let glob = globset::Glob::new(&installer.include_pattern())
+            .unwrap()
+            .compile_matcher();
+
+        assert!(glob.is_match("MesloLGMNerdFont-Regular.ttf"));
+        assert!(glob.is_match("fonts/MesloLGMNerdFont-Bold.ttf"));
+        assert!(!glob.is_match("MesloLGMNerdFont-Italic.ttf"));
+        assert!(!glob.is_match("MesloLGMNerdFontMono-Regular.ttf"));
+        assert!(!glob.is_match("MesloLGSNerdFont-Regular.ttf"));
+        assert!(!glob.is_match("README.md"));
+    }
+
+    #[test]
+    fn test_names_registry_values() {
+        let installer = meslo();
+        assert_eq!(
+            installer.registry_value(Path::new("C:\\Fonts\\MesloLGMNerdFont-Bold.ttf")),
+            "MesloLGM Nerd Font Bold (TrueType)"
+        );
+        assert_eq!(
+            installer.registry_value(Path::new("MesloLGMNerdFont-Italic.otf")),
+            "MesloLGM Nerd Font Italic (OpenType)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loads_fonts_into_the_session() {
+        let shell = FakeShell::new().on_run("AddFontResourceW", "");
+        let files = [PathBuf::from("C:/Fonts/it's.ttf")];
+        NerdFontInstaller::load(&shell, &files).await.unwrap();
+
+        let script = &shell.calls()[0];
+        assert!(script.contains("foreach ($file in @('C:/Fonts/it''s.ttf'))"));
+        assert!(script.contains("SendMessageTimeoutW([IntPtr]0xffff, 0x1D"));
+    }
+
+    #[test]
+    fn test_matches_family_name() {
+        let installer = meslo();
+
+        assert!(installer.matches_family("MesloLGM Nerd Font,MesloLGM NF"));
+        assert!(!installer.matches_family("MesloLGM Nerd Font Mono,MesloLGM NFM"));
+        assert!(installer.matches_family(
+            "    MesloLGM Nerd Font Bold (TrueType)    REG_SZ    C:\\Fonts\\a.ttf"
+        ));
+        assert!(!installer.matches_family(
+            "    MesloLGM Nerd Font Mono Bold (TrueType)    REG_SZ    C:\\Fonts\\b.ttf"
+        ));
+        assert!(installer.matches_family(
+            "    MesloLGM Nerd Font Bold Italic (TrueType)    REG_SZ    C:\\Fonts\\c.ttf"
+        ));
+        assert!(installer
+            .matches_family("    MesloLGM Nerd Font (OpenType)    REG_SZ    C:\\Fonts\\d.otf"));
+        assert!(!installer.matches_family(
+            "    MesloLGM Nerd Font Propo (TrueType)    REG_SZ    C:\\Fonts\\e.ttf"
+        ));
+        assert!(!installer
+            .matches_family("    MesloLGMDZ Nerd Font (TrueType)    REG_SZ    C:\\Fonts\\f.ttf"));
+    }
+}
@@ -1,17 +1,28 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use async_trait::async_trait;
-use colored::Colorize;
 use std::process::Stdio;
+use std::sync::Arc;
 use tokio::process::Command;
+use tracing::{debug, info};
 
 use crate::domain::interfaces::Installer;
+use crate::domain::InstallOutcome;
+use crate::infrastructure::process::{run_winget, RunLog, WINGET_FLAGS};
 
 /// Oh-My-Posh installer using winget
-pub struct OhMyPoshInstaller;
+pub struct OhMyPoshInstaller {
+    log: Option<Arc<RunLog>>,
+}
 
 impl OhMyPoshInstaller {
     pub fn new() -> Self {
-        Self
+        Self { log: None }
+    }
+
+    /// Record installer output in the run log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.log = Some(log);
+        self
     }
 }
 
@@ -23,26 +34,26 @@ impl Default for OhMyPoshInstaller {
 
 #[async_trait]
 impl Installer for OhMyPoshInstaller {
-    async fn install(&self) -> Result<()> {
-        println!(
-            "{} {}",
-            "➡".blue(),
-            "winget install JanDeDobbeleer.OhMyPosh -s winget".bright_black()
-        );
-
-        let status = Command::new("winget")
+    async fn install(&self) -> Result<InstallOutcome> {
+        debug!("➡ winget install JanDeDobbeleer.OhMyPosh -s winget");
+
+        let mut command = Command::new("winget");
+        command
+            .kill_on_drop(true)
             .args(["install", "JanDeDobbeleer.OhMyPosh", "-s", "winget"])
-            .status()
-            .await
-            .context("Failed to execute winget")?;
+            .args(WINGET_FLAGS);
+        let outcome = run_winget(
+            &mut command,
+            "winget install oh-my-posh",
+            self.log.as_deref(),
+        )
+        .await?;
 
-        if !status.success() {
-            anyhow::bail!("winget install oh-my-posh failed");
+        if outcome != InstallOutcome::AlreadyPresent {
+            info!("✅ oh-my-posh installed");
         }
 
-        println!("{} {}", "✅".green(), "oh-my-posh installed".green());
-
-        Ok(())
+        Ok(outcome)
     }
 
     async fn is_installed(&self) -> bool {
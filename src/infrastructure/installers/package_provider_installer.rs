@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::domain::interfaces::{Installer, ShellRunner};
+use crate::domain::{quote, InstallOutcome, InstallScope, ModuleBackend};
+use crate::infrastructure::installers::ModuleInstaller;
+
+/// Minimum NuGet provider version accepted by PowerShellGet
+const NUGET_MIN_VERSION: &str = "2.8.5.201";
+
+/// Bootstraps the module gallery of a backend so that module installs never
+/// stop at an interactive prompt: the NuGet provider and PSGallery trust for
+/// PowerShellGet, repository trust for PSResourceGet
+pub struct PackageProviderInstaller {
+    shell: Arc<dyn ShellRunner>,
+    backend: ModuleBackend,
+    scope: InstallScope,
+}
+
+impl PackageProviderInstaller {
+    /// `backend` is the one the module installers use; `Auto` is resolved on first use
+    pub fn new(shell: Arc<dyn ShellRunner>, backend: ModuleBackend) -> Self {
+        Self {
+            shell,
+            backend,
+            scope: InstallScope::CurrentUser,
+        }
+    }
+
+    /// Install the NuGet provider for all users, like the modules (needs elevation)
+    pub fn with_scope(mut self, scope: InstallScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    async fn resolve_backend(&self) -> ModuleBackend {
+        match self.backend {
+            ModuleBackend::Auto => ModuleInstaller::detect_backend(self.shell.as_ref()).await,
+            backend => backend,
+        }
+    }
+
+    /// Script printing `True` when the gallery can be used without prompts
+    fn detect_script(backend: ModuleBackend) -> String {
+        match backend {
+            ModuleBackend::PSResourceGet => {
+                "[bool](Get-PSResourceRepository -Name PSGallery -ErrorAction SilentlyContinue).Trusted"
+                    .to_string()
+            }
+            _ => format!(
+                r#"$nuget = Get-PackageProvider -ListAvailable -Name NuGet -ErrorAction SilentlyContinue |
+    Where-Object {{ $_.Version -ge [version]{min} }}
+$repo = Get-PSRepository -Name PSGallery -ErrorAction SilentlyContinue
+[bool]$nuget -and $repo.InstallationPolicy -eq 'Trusted'"#,
+                min = quote(NUGET_MIN_VERSION)
+            ),
+        }
+    }
+
+    /// Script installing the provider and trusting PSGallery without prompting
+    fn bootstrap_script(&self, backend: ModuleBackend) -> String {
+        let steps = match backend {
+            ModuleBackend::PSResourceGet => {
+                "Set-PSResourceRepository -Name PSGallery -Trusted".to_string()
+            }
+            _ => format!(
+                r#"[Net.ServicePointManager]::SecurityProtocol = [Net.ServicePointManager]::SecurityProtocol -bor [Net.SecurityProtocolType]::Tls12
+$nuget = Get-PackageProvider -ListAvailable -Name NuGet -ErrorAction SilentlyContinue |
+    Where-Object {{ $_.Version -ge [version]{min} }}
+if (-not $nuget) {{
+    Install-PackageProvider -Name NuGet -MinimumVersion {min} -Scope {scope} -Force -ForceBootstrap | Out-Null
+}}
+Set-PSRepository -Name PSGallery -InstallationPolicy Trusted"#,
+                min = quote(NUGET_MIN_VERSION),
+                scope = self.scope.as_powershell()
+            ),
+        };
+        format!(
+            "$ErrorActionPreference = 'Stop'\n$ProgressPreference = 'SilentlyContinue'\n{}",
+            steps
+        )
+    }
+}
+
+#[async_trait]
+impl Installer for PackageProviderInstaller {
+    async fn install(&self) -> Result<InstallOutcome> {
+        self.shell
+            .run(&self.bootstrap_script(self.resolve_backend().await))
+            .await
+            .context("Failed to bootstrap NuGet provider and PSGallery trust")?;
+
+        info!("✅ NuGet provider ready and PSGallery trusted");
+
+        Ok(InstallOutcome::Installed)
+    }
+
+    async fn is_installed(&self) -> bool {
+        let script = Self::detect_script(self.resolve_backend().await);
+        match self.shell.run(&script).await {
+            Ok(output) => output.trim().eq_ignore_ascii_case("true"),
+            Err(_) => false,
+        }
+    }
+
+    fn component_name(&self) -> &str {
+        "NuGet provider"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(180)
+    }
+
+    fn scope(&self) -> InstallScope {
+        self.scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::FakeShell;
+
+    #[tokio::test]
+    async fn test_bootstraps_the_forced_backend() {
+        // PSResourceGet is present, but the settings force PowerShellGet
+        let shell = Arc::new(
+            FakeShell::new()
+                .on_run("Install-PSResource", "True")
+                .on_run("Get-PackageProvider", ""),
+        );
+        let installer = PackageProviderInstaller::new(shell.clone(), ModuleBackend::PowerShellGet)
+            .with_scope(InstallScope::AllUsers);
+
+        assert!(!installer.is_installed().await);
+        installer.install().await.unwrap();
+
+        let calls = shell.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[1].contains("Install-PackageProvider -Name NuGet"));
+        assert!(calls[1].contains("-Scope AllUsers -Force"));
+        assert!(calls[1].contains("Set-PSRepository -Name PSGallery -InstallationPolicy Trusted"));
+        assert!(!calls[1].contains("Set-PSResourceRepository"));
+    }
+}
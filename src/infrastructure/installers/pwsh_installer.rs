@@ -1,21 +1,104 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use colored::Colorize;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::Command;
+use tracing::{info, warn};
 
-use crate::domain::interfaces::{Downloader, Installer};
+use crate::domain::interfaces::{Downloader, Extractor, Installer};
+use crate::domain::{quote, ArchiveKind, ExtractOptions, InstallOutcome, InstallScope};
+use crate::infrastructure::filesystem::TempFile;
+use crate::infrastructure::process::{run_captured, run_msiexec, RunLog};
+use crate::infrastructure::shell::host_args;
 
 const GITHUB_RELEASES: &str = "https://github.com/PowerShell/PowerShell/releases/latest";
 
+/// Machine-wide location of the portable copy on Linux and macOS; owned by
+/// this tool, unlike `/opt/microsoft/powershell`, which packages manage
+const SYSTEM_INSTALL_DIR: &str = "/opt/setup_powershell/pwsh";
+const SYSTEM_BIN_DIR: &str = "/usr/local/bin";
+
 /// PowerShell 7 installer
+///
+/// Installs the MSI machine-wide on Windows when elevated, otherwise a portable
+/// copy from the release zip or tarball.
 pub struct PwshInstaller {
-    downloader: std::sync::Arc<dyn Downloader>,
+    downloader: Arc<dyn Downloader>,
+    extractor: Arc<dyn Extractor>,
+    scope: InstallScope,
+    log: Option<Arc<RunLog>>,
 }
 
 impl PwshInstaller {
-    pub fn new(downloader: std::sync::Arc<dyn Downloader>) -> Self {
-        Self { downloader }
+    pub fn new(downloader: Arc<dyn Downloader>, extractor: Arc<dyn Extractor>) -> Self {
+        Self {
+            downloader,
+            extractor,
+            scope: InstallScope::CurrentUser,
+            log: None,
+        }
+    }
+
+    /// Record installer output in the run log; msiexec writes a verbose companion log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    /// Install for all users instead of the current user (needs elevation)
+    pub fn with_scope(mut self, scope: InstallScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Directory receiving the portable copy
+    pub fn portable_dir(scope: InstallScope) -> Option<PathBuf> {
+        match scope {
+            InstallScope::AllUsers if !cfg!(windows) => Some(PathBuf::from(SYSTEM_INSTALL_DIR)),
+            _ if cfg!(windows) => dirs::data_local_dir()
+                .map(|dir| dir.join("Programs").join("setup_powershell").join("pwsh")),
+            _ => dirs::data_local_dir().map(|dir| dir.join("setup_powershell").join("pwsh")),
+        }
+    }
+
+    /// Directory receiving the `pwsh` link on Linux and macOS; `~/.local/bin`
+    /// where the platform has no user bin directory, like macOS
+    fn bin_dir(scope: InstallScope) -> Option<PathBuf> {
+        match scope {
+            InstallScope::AllUsers => Some(PathBuf::from(SYSTEM_BIN_DIR)),
+            InstallScope::CurrentUser => dirs::executable_dir()
+                .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("bin"))),
+        }
+    }
+
+    /// Refuse to replace a `pwsh` at `link` unless it is missing or a link
+    /// into the tool-owned `target`
+    fn check_link(link: &Path, target: &Path) -> Result<()> {
+        match std::fs::symlink_metadata(link) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to inspect {}", link.display())),
+            Ok(metadata)
+                if metadata.is_symlink()
+                    && std::fs::read_link(link).is_ok_and(|dest| dest.starts_with(target)) =>
+            {
+                Ok(())
+            }
+            Ok(_) => anyhow::bail!(
+                "{} already exists and was not installed by this tool; remove it or put {} on PATH yourself",
+                link.display(),
+                target.display()
+            ),
+        }
+    }
+
+    fn executable_name() -> &'static str {
+        if cfg!(windows) {
+            "pwsh.exe"
+        } else {
+            "pwsh"
+        }
     }
 
     async fn get_latest_version(&self) -> Result<String> {
@@ -37,80 +120,276 @@ impl PwshInstaller {
 
         Ok(version.to_string())
     }
-}
 
-#[async_trait]
-impl Installer for PwshInstaller {
-    async fn install(&self) -> Result<()> {
-        println!(
-            "{} {}",
-            "🔍".cyan(),
-            "Checking latest PowerShell release...".cyan()
+    async fn download(&self, version: &str, asset_name: &str) -> Result<TempFile> {
+        let download_url = format!(
+            "https://github.com/PowerShell/PowerShell/releases/download/{}/{}",
+            version, asset_name
         );
+        let asset = TempFile::in_temp_dir(asset_name);
 
-        let version = self.get_latest_version().await?;
+        info!("⬇ Downloading {} ...", asset_name);
 
-        println!(
-            "{} {}",
-            "✅".green(),
-            format!("Latest PowerShell: {}", version).green()
-        );
+        self.downloader
+            .download(&download_url, asset.path())
+            .await?;
 
-        let version_number = version.strip_prefix('v').unwrap_or(&version);
-        let msi_name = format!("PowerShell-{}-win-x64.msi", version_number);
-        let download_url = format!(
-            "https://github.com/PowerShell/PowerShell/releases/download/{}/{}",
-            version, msi_name
-        );
+        info!("✅ Downloaded to {}", asset.path().display());
 
-        let temp_dir = std::env::temp_dir();
-        let msi_path = temp_dir.join(&msi_name);
+        Ok(asset)
+    }
 
-        println!(
-            "{} {}",
-            "⬇".blue(),
-            format!("Downloading {} ...", msi_name).blue()
-        );
+    async fn install_msi(&self, version: &str) -> Result<InstallOutcome> {
+        let version_number = version.strip_prefix('v').unwrap_or(version);
+        let msi_name = format!("PowerShell-{}-win-{}.msi", version_number, release_arch());
+        let msi = self.download(version, &msi_name).await?;
 
-        self.downloader.download(&download_url, &msi_path).await?;
+        info!("⚙ Installing PowerShell 7 for all users...");
 
-        println!(
-            "{} {}",
-            "✅".green(),
-            format!("Downloaded to {}", msi_path.display()).green()
-        );
+        let mut command = Command::new("msiexec");
+        command.kill_on_drop(true).args([
+            "/i",
+            msi.path().to_str().unwrap(),
+            "/quiet",
+            "/norestart",
+        ]);
+        if let Some(log) = &self.log {
+            command.arg("/l*v").arg(log.companion_path("msiexec"));
+        }
+        run_msiexec(&mut command, "msiexec PowerShell 7", self.log.as_deref()).await
+    }
+
+    async fn install_portable(&self, version: &str) -> Result<InstallOutcome> {
+        let version_number = version.strip_prefix('v').unwrap_or(version);
+        let (asset_name, kind) = portable_asset(version_number)?;
+        let target =
+            Self::portable_dir(self.scope).context("Could not determine install directory")?;
+        // Checked before downloading, so that a conflict costs nothing
+        let link = if cfg!(windows) {
+            None
+        } else {
+            let bin_dir = Self::bin_dir(self.scope).context("Could not determine bin directory")?;
+            let link = bin_dir.join("pwsh");
+            Self::check_link(&link, &target)?;
+            Some(link)
+        };
+        let archive = self.download(version, &asset_name).await?;
 
-        println!(
-            "{} {}",
-            "⚙".yellow(),
-            "Installing PowerShell 7 (may need admin rights)...".yellow()
+        info!(
+            "⚙ Installing portable PowerShell 7 to {} ...",
+            target.display()
         );
 
-        let status = Command::new("msiexec")
-            .args(["/i", msi_path.to_str().unwrap(), "/quiet", "/norestart"])
-            .status()
+        // Only ever a directory of this tool, so an older copy can be replaced
+        if target.exists() {
+            tokio::fs::remove_dir_all(&target)
+                .await
+                .with_context(|| format!("Failed to remove {}", target.display()))?;
+        }
+        self.extractor
+            .extract(archive.path(), kind, &target, &ExtractOptions::default())
             .await?;
 
-        if status.success() {
-            println!("{} {}", "✅".green(), "PowerShell 7 installed!".green());
-        } else {
-            anyhow::bail!("Installation failed with status: {}", status);
+        let executable = target.join(Self::executable_name());
+        if !executable.exists() {
+            anyhow::bail!("{} not found in {}", Self::executable_name(), asset_name);
+        }
+        self.link(&target, &executable, link.as_deref()).await?;
+
+        Ok(InstallOutcome::Installed)
+    }
+
+    /// Put the portable copy on PATH: a symlink at `link` on Unix, the user
+    /// PATH variable on Windows
+    async fn link(&self, target: &Path, executable: &Path, link: Option<&Path>) -> Result<()> {
+        if cfg!(windows) {
+            let script = format!(
+                "$path = [Environment]::GetEnvironmentVariable('Path', 'User'); \
+                 if (($path -split ';') -notcontains {dir}) {{ \
+                 [Environment]::SetEnvironmentVariable('Path', ((@($path, {dir}) | Where-Object {{ $_ }}) -join ';'), 'User') }}",
+                dir = quote(&target.display().to_string())
+            );
+            let mut command = Command::new("powershell");
+            command
+                .kill_on_drop(true)
+                .args(host_args(&script))
+                .stdin(Stdio::null());
+            run_captured(&mut command, "add pwsh to user PATH", self.log.as_deref()).await?;
+            return Ok(());
+        }
+
+        let Some(link) = link else {
+            return Ok(());
+        };
+        if let Some(bin_dir) = link.parent() {
+            tokio::fs::create_dir_all(bin_dir).await?;
+        }
+        // check_link made sure this is only ever our own link
+        if tokio::fs::symlink_metadata(link).await.is_ok() {
+            tokio::fs::remove_file(link)
+                .await
+                .with_context(|| format!("Failed to replace {}", link.display()))?;
+        }
+
+        #[cfg(unix)]
+        {
+            // The release tarballs do not always carry the executable bit
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(executable, std::fs::Permissions::from_mode(0o755)).await?;
         }
+        #[cfg(unix)]
+        tokio::fs::symlink(executable, link)
+            .await
+            .with_context(|| format!("Failed to link {}", link.display()))?;
+        #[cfg(not(unix))]
+        let _ = executable;
 
         Ok(())
     }
+}
+
+/// Architecture suffix used in PowerShell release asset names
+fn release_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "aarch64" => "arm64",
+        "x86" => "x86",
+        "arm" => "arm32",
+        _ => "x64",
+    }
+}
+
+/// Release asset name and archive kind of the portable build for this platform
+fn portable_asset(version_number: &str) -> Result<(String, ArchiveKind)> {
+    let arch = release_arch();
+    match std::env::consts::OS {
+        "windows" => Ok((
+            format!("PowerShell-{}-win-{}.zip", version_number, arch),
+            ArchiveKind::Zip,
+        )),
+        "linux" => Ok((
+            format!("powershell-{}-linux-{}.tar.gz", version_number, arch),
+            ArchiveKind::TarGz,
+        )),
+        "macos" => Ok((
+            format!("powershell-{}-osx-{}.tar.gz", version_number, arch),
+            ArchiveKind::TarGz,
+        )),
+        os => anyhow::bail!("No portable PowerShell build for {}", os),
+    }
+}
+
+#[async_trait]
+impl Installer for PwshInstaller {
+    async fn install(&self) -> Result<InstallOutcome> {
+        info!("🔍 Checking latest PowerShell release...");
+
+        let version = self.get_latest_version().await?;
+
+        info!("✅ Latest PowerShell: {}", version);
+
+        let outcome = if cfg!(windows) && self.scope == InstallScope::AllUsers {
+            self.install_msi(&version).await?
+        } else {
+            self.install_portable(&version).await?
+        };
+
+        match outcome {
+            InstallOutcome::Installed => {
+                info!("✅ PowerShell 7 installed!")
+            }
+            InstallOutcome::RestartRequired => {
+                warn!("PowerShell 7 installed, restart Windows to finish.")
+            }
+            InstallOutcome::AlreadyPresent => {}
+        }
+
+        Ok(outcome)
+    }
 
     async fn is_installed(&self) -> bool {
-        Command::new("pwsh")
+        let on_path = Command::new("pwsh")
             .arg("-Version")
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .status()
             .await
-            .is_ok()
+            .is_ok();
+
+        on_path
+            || Self::portable_dir(self.scope)
+                .is_some_and(|dir| dir.join(Self::executable_name()).exists())
     }
 
     fn component_name(&self) -> &str {
         "PowerShell 7"
     }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(900)
+    }
+
+    fn scope(&self) -> InstallScope {
+        self.scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_dir_is_tool_owned() {
+        for scope in [InstallScope::AllUsers, InstallScope::CurrentUser] {
+            if let Some(dir) = PwshInstaller::portable_dir(scope) {
+                assert!(dir.to_string_lossy().contains("setup_powershell"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_portable_asset_for_platform() {
+        let (name, kind) = portable_asset("7.4.6").unwrap();
+
+        assert!(name.contains("7.4.6"));
+        assert!(name.contains(release_arch()));
+        if cfg!(windows) {
+            assert_eq!(kind, ArchiveKind::Zip);
+        } else {
+            assert_eq!(kind, ArchiveKind::TarGz);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_only_replaces_own_link() {
+        let dir = std::env::temp_dir().join("setup_powershell_pwsh_link");
+        let _ = std::fs::remove_dir_all(&dir);
+        let target = dir.join("pwsh");
+        std::fs::create_dir_all(&target).unwrap();
+        let link = dir.join("bin-pwsh");
+
+        let missing = PwshInstaller::check_link(&link, &target);
+        std::os::unix::fs::symlink(target.join("pwsh"), &link).unwrap();
+        let own = PwshInstaller::check_link(&link, &target);
+        std::fs::remove_file(&link).unwrap();
+        std::os::unix::fs::symlink("/usr/bin/pwsh", &link).unwrap();
+        let foreign = PwshInstaller::check_link(&link, &target);
+        std::fs::remove_file(&link).unwrap();
+        std::fs::write(&link, "#!/bin/sh").unwrap();
+        let file = PwshInstaller::check_link(&link, &target);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(missing.is_ok());
+        assert!(own.is_ok());
+        assert!(foreign.is_err());
+        assert!(file
+            .unwrap_err()
+            .to_string()
+            .contains("not installed by this tool"));
+    }
+
+    #[test]
+    fn test_user_bin_dir_always_resolves() {
+        assert!(PwshInstaller::bin_dir(InstallScope::CurrentUser).is_some());
+    }
 }
@@ -0,0 +1,138 @@
+use colored::Colorize;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::format::{FmtSpan, Writer};
+use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing_subscriber::fmt::{self, FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::domain::Verbosity;
+use crate::infrastructure::process::RunLog;
+
+/// `tracing` subscriber printing events to the console at the chosen verbosity
+/// and writing everything down to debug level to the run log
+///
+/// Spans prefix the events inside them in the log file and report their
+/// duration when they close.
+pub struct LogSubscriber {
+    console: LevelFilter,
+    file: Option<Arc<RunLog>>,
+}
+
+impl LogSubscriber {
+    pub fn new(verbosity: Verbosity) -> Self {
+        let console = match verbosity {
+            Verbosity::Quiet => LevelFilter::WARN,
+            Verbosity::Normal => LevelFilter::INFO,
+            Verbosity::Verbose => LevelFilter::DEBUG,
+            Verbosity::Trace => LevelFilter::TRACE,
+        };
+        Self {
+            console,
+            file: None,
+        }
+    }
+
+    /// Also write debug-level events and span timings to the run log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.file = Some(log);
+        self
+    }
+
+    /// Subscriber with a console layer and, given a run log, a file layer
+    pub fn build(self) -> impl Subscriber + Send + Sync {
+        let verbose = self.console >= LevelFilter::DEBUG;
+        let console = fmt::layer()
+            .with_span_events(if verbose {
+                FmtSpan::CLOSE
+            } else {
+                FmtSpan::NONE
+            })
+            .event_format(ConsoleFormat { spans: verbose })
+            .with_writer(
+                std::io::stderr
+                    .with_max_level(Level::WARN)
+                    .or_else(std::io::stdout),
+            )
+            .with_filter(self.console);
+
+        let file_level = self.console.max(LevelFilter::DEBUG);
+        let file = self.file.map(|log| {
+            fmt::layer()
+                .with_writer(log)
+                .with_target(false)
+                .with_timer(fmt::time::uptime())
+                .with_span_events(FmtSpan::CLOSE)
+                .with_filter(file_level)
+        });
+
+        tracing_subscriber::registry().with(console).with(file)
+    }
+}
+
+/// Console lines: warnings and errors highlighted, debug output dimmed and,
+/// when verbose, prefixed with the spans the event happened in
+struct ConsoleFormat {
+    spans: bool,
+}
+
+impl<S, N> FormatEvent<S, N> for ConsoleFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let mut message = String::new();
+        if let Some(scope) = ctx.event_scope().filter(|_| self.spans) {
+            for span in scope.from_root() {
+                write!(message, "{}", span.name())?;
+                if let Some(fields) = span.extensions().get::<FormattedFields<N>>() {
+                    write!(message, "{{{}}}", fields)?;
+                }
+                message.push_str(": ");
+            }
+        }
+        ctx.format_fields(Writer::new(&mut message), event)?;
+
+        match *event.metadata().level() {
+            Level::ERROR => writeln!(writer, "{} {}", "✗".red(), message.red()),
+            Level::WARN => writeln!(writer, "{} {}", "⚠".yellow(), message.yellow()),
+            Level::INFO => writeln!(writer, "{}", message),
+            _ => writeln!(writer, "{}", message.bright_black()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_spans_and_timings_to_log() {
+        let path = std::env::temp_dir().join("setup_powershell_subscriber.log");
+        let log = Arc::new(RunLog::at(&path).unwrap());
+        let subscriber = LogSubscriber::new(Verbosity::Quiet).with_log(log).build();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let phase = tracing::info_span!("phase", name = "modules");
+            let _entered = phase.enter();
+            tracing::debug!(module = "z", "Installing");
+            tracing::trace!("not written below debug");
+        });
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(content.contains("DEBUG phase{name=\"modules\"}: Installing module=\"z\""));
+        assert!(content.contains("phase{name=\"modules\"}: close time.busy="));
+        assert!(!content.contains("not written"));
+    }
+}
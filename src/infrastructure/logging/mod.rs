@@ -0,0 +1,3 @@
+pub mod log_subscriber;
+
+pub use log_subscriber::LogSubscriber;
@@ -1,13 +1,24 @@
+pub mod archive;
+pub mod config;
 pub mod filesystem;
 pub mod installers;
+pub mod logging;
 pub mod network;
+pub mod process;
+pub mod profile;
 pub mod shell;
 pub mod terminal;
 
-pub use filesystem::ProfileFsWriter;
+pub use archive::ArchiveExtractor;
+pub use config::SettingsFile;
+pub use filesystem::{ProfileFsWriter, TempFile};
 pub use installers::{
-    FontInstaller, FzfInstaller, ModuleInstaller, OhMyPoshInstaller, PwshInstaller,
+    FzfInstaller, GithubToolInstaller, ModuleInstaller, NerdFontInstaller, OhMyPoshInstaller,
+    PackageProviderInstaller, PwshInstaller,
 };
+pub use logging::LogSubscriber;
 pub use network::HttpDownloader;
-pub use shell::PowerShellRunner;
+pub use process::{is_elevated, RunLog};
+pub use profile::{FishRenderer, NushellRenderer, PosixRenderer, PowerShellRenderer};
+pub use shell::{FakeShell, PowerShellRunner, PowerShellSession, RecordingShell};
 pub use terminal::WindowsTerminalConfig;
@@ -6,6 +6,7 @@ use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
 use crate::domain::interfaces::Downloader;
+use crate::infrastructure::filesystem::TempFile;
 
 /// HTTP file downloader using reqwest
 pub struct HttpDownloader {
@@ -14,9 +15,12 @@ pub struct HttpDownloader {
 
 impl HttpDownloader {
     pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::new(),
-        }
+        // The GitHub API rejects requests without a user agent
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("setup_powershell/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
     }
 }
 
@@ -35,7 +39,12 @@ impl Downloader for HttpDownloader {
             anyhow::bail!("Failed to download: HTTP {}", response.status());
         }
 
-        let mut file = fs::File::create(path).await?;
+        // Stream into a sibling `.part` file that is removed if the download is interrupted
+        let mut part_name = path.as_os_str().to_owned();
+        part_name.push(".part");
+        let part = TempFile::new(part_name);
+
+        let mut file = fs::File::create(part.path()).await?;
         let mut stream = response.bytes_stream();
 
         while let Some(chunk) = stream.next().await {
@@ -44,6 +53,10 @@ impl Downloader for HttpDownloader {
         }
 
         file.flush().await?;
+        drop(file);
+
+        fs::rename(part.path(), path).await?;
+        part.persist();
         Ok(())
     }
 }
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use tokio::process::Command;
+
+use super::run_log::{capture, failure, RunLog};
+use crate::domain::{ActionableError, InstallOutcome};
+
+// winget HRESULTs (see winget-cli `AppInstallerErrors.h`)
+const WINGET_NO_APPLICATIONS_FOUND: u32 = 0x8A15_0014;
+const WINGET_UPDATE_NOT_APPLICABLE: u32 = 0x8A15_002B;
+const WINGET_PACKAGE_AGREEMENTS_NOT_ACCEPTED: u32 = 0x8A15_0041;
+const WINGET_SOURCE_AGREEMENTS_NOT_ACCEPTED: u32 = 0x8A15_0046;
+const WINGET_PACKAGE_ALREADY_INSTALLED: u32 = 0x8A15_0061;
+const WINGET_INSTALL_PACKAGE_IN_USE: u32 = 0x8A15_0101;
+const WINGET_INSTALL_IN_PROGRESS: u32 = 0x8A15_0102;
+const WINGET_REBOOT_REQUIRED_TO_FINISH: u32 = 0x8A15_0109;
+
+// Windows Installer exit codes
+const MSI_USER_EXIT: i32 = 1602;
+const MSI_FATAL_ERROR: i32 = 1603;
+const MSI_ANOTHER_INSTALL_IN_PROGRESS: i32 = 1618;
+const MSI_BLOCKED_BY_POLICY: i32 = 1625;
+const MSI_PRODUCT_VERSION_INSTALLED: i32 = 1638;
+const MSI_REBOOT_INITIATED: i32 = 1641;
+const MSI_REBOOT_REQUIRED: i32 = 3010;
+
+/// Known cause of an installer exit code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExitCodeMeaning {
+    Outcome(InstallOutcome),
+    /// Failure the user can fix; carries the hint
+    Actionable(&'static str),
+    Unknown,
+}
+
+/// Interpret a winget exit code, falling back to its output for agreement prompts
+pub fn interpret_winget(code: i32, output: &str) -> ExitCodeMeaning {
+    match code as u32 {
+        0 => ExitCodeMeaning::Outcome(InstallOutcome::Installed),
+        WINGET_PACKAGE_ALREADY_INSTALLED | WINGET_UPDATE_NOT_APPLICABLE => {
+            ExitCodeMeaning::Outcome(InstallOutcome::AlreadyPresent)
+        }
+        WINGET_REBOOT_REQUIRED_TO_FINISH => {
+            ExitCodeMeaning::Outcome(InstallOutcome::RestartRequired)
+        }
+        WINGET_NO_APPLICATIONS_FOUND => ExitCodeMeaning::Actionable(
+            "The package was not found; run `winget source update` and check the package id",
+        ),
+        WINGET_PACKAGE_AGREEMENTS_NOT_ACCEPTED | WINGET_SOURCE_AGREEMENTS_NOT_ACCEPTED => {
+            ExitCodeMeaning::Actionable(
+                "Agreements were not accepted; run winget once interactively to accept them",
+            )
+        }
+        WINGET_INSTALL_PACKAGE_IN_USE => {
+            ExitCodeMeaning::Actionable("The application is running; close it and retry")
+        }
+        WINGET_INSTALL_IN_PROGRESS => ExitCodeMeaning::Actionable(
+            "Another installation is in progress; wait for it to finish and retry",
+        ),
+        _ if output.contains("agreements") => ExitCodeMeaning::Actionable(
+            "Agreements were not accepted; run winget once interactively to accept them",
+        ),
+        _ => ExitCodeMeaning::Unknown,
+    }
+}
+
+/// Interpret a msiexec exit code
+pub fn interpret_msiexec(code: i32) -> ExitCodeMeaning {
+    match code {
+        0 => ExitCodeMeaning::Outcome(InstallOutcome::Installed),
+        MSI_REBOOT_REQUIRED | MSI_REBOOT_INITIATED => {
+            ExitCodeMeaning::Outcome(InstallOutcome::RestartRequired)
+        }
+        MSI_PRODUCT_VERSION_INSTALLED => ExitCodeMeaning::Outcome(InstallOutcome::AlreadyPresent),
+        MSI_USER_EXIT => ExitCodeMeaning::Actionable(
+            "The installation was cancelled, usually by declining the elevation prompt",
+        ),
+        MSI_FATAL_ERROR => ExitCodeMeaning::Actionable(
+            "Windows Installer failed; retry from an elevated terminal and check the msiexec log",
+        ),
+        MSI_ANOTHER_INSTALL_IN_PROGRESS => ExitCodeMeaning::Actionable(
+            "Another installation is in progress; wait for it to finish and retry",
+        ),
+        MSI_BLOCKED_BY_POLICY => ExitCodeMeaning::Actionable(
+            "Installation is blocked by system policy; ask your administrator",
+        ),
+        _ => ExitCodeMeaning::Unknown,
+    }
+}
+
+/// Turn an interpreted exit code into an outcome or an error
+///
+/// `failure` builds the generic error from the captured output.
+fn into_outcome(
+    meaning: ExitCodeMeaning,
+    failure: impl FnOnce() -> anyhow::Error,
+) -> Result<InstallOutcome> {
+    match meaning {
+        ExitCodeMeaning::Outcome(outcome) => Ok(outcome),
+        ExitCodeMeaning::Actionable(hint) => Err(ActionableError {
+            message: failure().to_string(),
+            hint: hint.to_string(),
+        }
+        .into()),
+        ExitCodeMeaning::Unknown => Err(failure()),
+    }
+}
+
+/// Flags every `winget install` gets, so it never waits for an answer
+pub const WINGET_FLAGS: [&str; 3] = [
+    "--accept-source-agreements",
+    "--accept-package-agreements",
+    "--disable-interactivity",
+];
+
+/// Run winget with captured output and map its exit code to an outcome
+pub async fn run_winget(
+    command: &mut Command,
+    description: &str,
+    log: Option<&RunLog>,
+) -> Result<InstallOutcome> {
+    let output = capture(command, description, log).await?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let meaning = interpret_winget(output.status.code().unwrap_or(-1), &text);
+    into_outcome(meaning, || failure(description, &output, log))
+}
+
+/// Run msiexec with captured output and map its exit code to an outcome
+pub async fn run_msiexec(
+    command: &mut Command,
+    description: &str,
+    log: Option<&RunLog>,
+) -> Result<InstallOutcome> {
+    let output = capture(command, description, log).await?;
+    let meaning = interpret_msiexec(output.status.code().unwrap_or(-1));
+    into_outcome(meaning, || failure(description, &output, log))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_winget_codes() {
+        assert_eq!(
+            interpret_winget(0x8A15_0061_u32 as i32, ""),
+            ExitCodeMeaning::Outcome(InstallOutcome::AlreadyPresent)
+        );
+        assert!(matches!(
+            interpret_winget(0x8A15_0014_u32 as i32, ""),
+            ExitCodeMeaning::Actionable(_)
+        ));
+        assert!(matches!(
+            interpret_winget(1, "Do you agree to all the source agreements terms?"),
+            ExitCodeMeaning::Actionable(_)
+        ));
+        assert_eq!(interpret_winget(1, "boom"), ExitCodeMeaning::Unknown);
+    }
+
+    #[test]
+    fn test_msiexec_codes() {
+        assert_eq!(
+            interpret_msiexec(3010),
+            ExitCodeMeaning::Outcome(InstallOutcome::RestartRequired)
+        );
+        assert!(matches!(
+            interpret_msiexec(1603),
+            ExitCodeMeaning::Actionable(_)
+        ));
+    }
+}
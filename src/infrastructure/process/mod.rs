@@ -0,0 +1,7 @@
+pub mod exit_codes;
+pub mod privilege;
+pub mod run_log;
+
+pub use exit_codes::{run_msiexec, run_winget, WINGET_FLAGS};
+pub use privilege::is_elevated;
+pub use run_log::{capture, run_captured, RunLog};
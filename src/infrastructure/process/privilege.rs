@@ -0,0 +1,51 @@
+use tokio::process::Command;
+
+/// Mandatory integrity levels of an elevated Windows token (High, System)
+const ELEVATED_INTEGRITY_SIDS: [&str; 2] = ["S-1-16-12288", "S-1-16-16384"];
+
+/// Whether the process runs elevated: as administrator on Windows, as root elsewhere
+pub async fn is_elevated() -> bool {
+    let output = if cfg!(windows) {
+        Command::new("whoami").arg("/groups").output().await
+    } else {
+        Command::new("id").arg("-u").output().await
+    };
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if cfg!(windows) {
+                has_elevated_integrity(&stdout)
+            } else {
+                stdout.trim() == "0"
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Whether `whoami /groups` output lists a high or system integrity level
+///
+/// A UAC-filtered administrator token is in the Administrators group but only
+/// at medium integrity, so group membership alone is not enough.
+fn has_elevated_integrity(groups: &str) -> bool {
+    groups
+        .lines()
+        .any(|line| ELEVATED_INTEGRITY_SIDS.iter().any(|sid| line.contains(sid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elevated_integrity() {
+        let filtered = "BUILTIN\\Administrators  Alias  S-1-5-32-544  Group used for deny only\n\
+                        Mandatory Label\\Medium Mandatory Level  Label  S-1-16-8192\n";
+        let elevated = "BUILTIN\\Administrators  Alias  S-1-5-32-544  Enabled group, Group owner\n\
+                        Mandatory Label\\High Mandatory Level  Label  S-1-16-12288\n";
+
+        assert!(!has_elevated_integrity(filtered));
+        assert!(has_elevated_integrity(elevated));
+    }
+}
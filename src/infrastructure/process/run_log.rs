@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::process::Command;
+use tracing::trace;
+
+/// Number of output lines included in error messages
+const TAIL_LINES: usize = 20;
+
+/// Per-run log file collecting the output of every external process
+pub struct RunLog {
+    path: PathBuf,
+    file: Mutex<std::fs::File>,
+}
+
+impl RunLog {
+    /// Create a new log under the local data directory (or the temp directory)
+    pub fn create() -> Result<Self> {
+        let dir = dirs::data_local_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("setup_powershell")
+            .join("logs");
+        std::fs::create_dir_all(&dir).context("Failed to create log directory")?;
+        Self::create_in(&dir)
+    }
+
+    /// Create `run-<unix seconds>-<pid>.log` in `dir`, adding a counter when
+    /// that file already exists so concurrent runs never share a log
+    fn create_in(dir: &Path) -> Result<Self> {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let name = format!("run-{}-{}", stamp, std::process::id());
+
+        let mut attempt = 0;
+        loop {
+            let path = match attempt {
+                0 => dir.join(format!("{}.log", name)),
+                n => dir.join(format!("{}-{}.log", name, n)),
+            };
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    return Ok(Self {
+                        path,
+                        file: Mutex::new(file),
+                    })
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => attempt += 1,
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to create log file {}", path.display()))
+                }
+            }
+        }
+    }
+
+    pub fn at(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("Failed to create log file {}", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path for a tool-specific log next to this one, e.g. `run-<stamp>.msiexec.log`
+    pub fn companion_path(&self, tool: &str) -> PathBuf {
+        self.path.with_extension(format!("{}.log", tool))
+    }
+
+    /// Append a single line, e.g. a log event
+    pub fn write_line(&self, line: &str) {
+        let mut file = self.file.lock().unwrap();
+        let _ = writeln!(file, "{}", line);
+    }
+
+    /// Append a command and its captured output
+    pub fn record(&self, description: &str, output: &Output) {
+        let mut file = self.file.lock().unwrap();
+        let _ = writeln!(file, "===== {} ({})", description, output.status);
+        let _ = file.write_all(&output.stdout);
+        if !output.stderr.is_empty() {
+            let _ = writeln!(file, "----- stderr");
+            let _ = file.write_all(&output.stderr);
+        }
+        let _ = writeln!(file);
+    }
+}
+
+/// Lets the run log receive formatted `tracing` output
+impl Write for &RunLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.lock().unwrap().flush()
+    }
+}
+
+/// Last lines of a process' stdout followed by stderr
+fn tail(output: &Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    lines[lines.len().saturating_sub(TAIL_LINES)..].join("\n")
+}
+
+/// Run a command with captured output, recording it in `log` whatever its exit status
+pub async fn capture(
+    command: &mut Command,
+    description: &str,
+    log: Option<&RunLog>,
+) -> Result<Output> {
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("Failed to execute {}", description))?;
+
+    if let Some(log) = log {
+        log.record(description, &output);
+    }
+    trace!(
+        "{} ({})\n{}{}",
+        description,
+        output.status,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(output)
+}
+
+/// Error for a failed command, carrying the last lines of its output
+pub fn failure(description: &str, output: &Output, log: Option<&RunLog>) -> anyhow::Error {
+    let mut message = format!("{} failed ({})", description, output.status);
+    let tail = tail(output);
+    if !tail.is_empty() {
+        message.push_str(&format!("\n{}", tail));
+    }
+    if let Some(log) = log {
+        message.push_str(&format!("\nFull log: {}", log.path().display()));
+    }
+    anyhow::anyhow!(message)
+}
+
+/// Run a command with captured output, recording it in `log`
+///
+/// A non-zero exit status becomes an error carrying the last lines of output.
+pub async fn run_captured(
+    command: &mut Command,
+    description: &str,
+    log: Option<&RunLog>,
+) -> Result<Output> {
+    let output = capture(command, description, log).await?;
+
+    if !output.status.success() {
+        return Err(failure(description, &output, log));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::ExitStatus;
+
+    #[cfg(unix)]
+    fn status(code: i32) -> ExitStatus {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatus::from_raw(code << 8)
+    }
+
+    #[cfg(windows)]
+    fn status(code: i32) -> ExitStatus {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatus::from_raw(code as u32)
+    }
+
+    #[test]
+    fn test_tail_keeps_last_lines() {
+        let stdout: String = (1..=30).map(|i| format!("line {}\n", i)).collect();
+        let output = Output {
+            status: status(1),
+            stdout: stdout.into_bytes(),
+            stderr: b"fatal: boom\n".to_vec(),
+        };
+
+        let tail = tail(&output);
+        assert_eq!(tail.lines().count(), TAIL_LINES);
+        assert!(tail.starts_with("line 12"));
+        assert!(tail.ends_with("fatal: boom"));
+    }
+
+    #[test]
+    fn test_runs_get_separate_logs() {
+        let dir = std::env::temp_dir().join("setup_powershell_run_logs");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = RunLog::create_in(&dir).unwrap();
+        let second = RunLog::create_in(&dir).unwrap();
+
+        assert_ne!(first.path(), second.path());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
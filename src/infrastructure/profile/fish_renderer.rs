@@ -0,0 +1,131 @@
+use super::shortcuts::{
+    command_functions, enabled_groups, expand_home, powershell_functions, unsupported_plugins,
+};
+use crate::domain::interfaces::ProfileRenderer;
+use crate::domain::{ProfileConfig, ProfileScript, StartupFile};
+
+/// fish single-quoted string literal
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// Renders the managed block of `~/.config/fish/config.fish`
+#[derive(Debug, Default)]
+pub struct FishRenderer;
+
+impl FishRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn content(&self, config: &ProfileConfig) -> String {
+        let mut content = String::from(
+            "# Generated by setup_powershell; changes inside this block are overwritten\n\n",
+        );
+
+        // Oh-My-Posh theme
+        content.push_str(&format!(
+            r#"# --- Oh-My-Posh prompt theme ---
+if type -q oh-my-posh
+    set -l themes ~/.cache/oh-my-posh/themes
+    set -q POSH_THEMES_PATH; and set themes $POSH_THEMES_PATH
+    if test -f $themes/{theme}
+        oh-my-posh init fish --config $themes/{theme} | source
+    else
+        oh-my-posh init fish | source
+    end
+end
+
+"#,
+            theme = fish_quote(&format!("{}.omp.json", config.theme))
+        ));
+
+        // fzf key bindings stand in for PSFzf
+        if config.plugins.iter().any(|plugin| plugin == "PSFzf") {
+            content.push_str(
+                r#"# --- fzf key bindings (Ctrl+T, Ctrl+R, Alt+C) ---
+if type -q fzf
+    fzf --fish | source
+end
+
+"#,
+            );
+        }
+
+        // Aliases, functions and environment
+        for group in enabled_groups(config) {
+            content.push_str(&format!("# --- {} ---\n", group.name));
+            for (name, target) in &group.aliases {
+                content.push_str(&format!("alias {} {}\n", name, fish_quote(target.posix())));
+            }
+            for (name, command) in command_functions(group) {
+                content.push_str(&format!("function {}\n    {} $argv\nend\n", name, command));
+            }
+            for (name, value) in &group.env {
+                content.push_str(&format!("set -gx {} {}\n", name, fish_quote(value)));
+            }
+            for dir in &group.path {
+                content.push_str(&format!(
+                    "set -gx PATH {} $PATH\n",
+                    fish_quote(&expand_home(dir))
+                ));
+            }
+            content.push('\n');
+        }
+
+        content
+    }
+}
+
+impl ProfileRenderer for FishRenderer {
+    fn shell_name(&self) -> &str {
+        "fish"
+    }
+
+    fn render(&self, config: &ProfileConfig) -> Vec<ProfileScript> {
+        vec![ProfileScript {
+            file: StartupFile::Home(".config/fish/config.fish".into()),
+            content: self.content(config),
+        }]
+    }
+
+    /// fish suggests commands from history on its own, covering PSReadLine
+    fn unsupported(&self, config: &ProfileConfig) -> Vec<String> {
+        let mut unsupported =
+            unsupported_plugins(config, self.shell_name(), &["PSFzf", "PSReadLine"]);
+        unsupported.extend(powershell_functions(config));
+        unsupported
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_fish_config() {
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec![
+                "PSReadLine".to_string(),
+                "PSFzf".to_string(),
+                "Terminal-Icons".to_string(),
+            ])
+            .build();
+        let renderer = FishRenderer::new();
+        let content = &renderer.render(&config)[0].content;
+
+        assert!(
+            content.contains("oh-my-posh init fish --config $themes/'paradox.omp.json' | source")
+        );
+        assert!(content.contains("fzf --fish | source"));
+        assert!(content.contains("function gco\n    git checkout $argv\nend\n"));
+        assert!(content.contains("function gs\n    git status $argv\nend\n"));
+        assert!(content.contains("alias la 'ls -la'\n"));
+        assert_eq!(
+            renderer.unsupported(&config),
+            vec!["Terminal-Icons has no fish equivalent and was skipped"]
+        );
+        assert_eq!(fish_quote(r"it's\"), r"'it\'s\\'");
+    }
+}
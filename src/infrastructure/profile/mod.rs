@@ -0,0 +1,11 @@
+pub mod fish_renderer;
+pub mod nushell_renderer;
+pub mod posix_renderer;
+pub mod powershell_renderer;
+pub mod shortcuts;
+
+pub use fish_renderer::FishRenderer;
+pub use nushell_renderer::NushellRenderer;
+pub use posix_renderer::PosixRenderer;
+pub use powershell_renderer::PowerShellRenderer;
+pub use shortcuts::{command_functions, enabled_groups, powershell_functions, unsupported_plugins};
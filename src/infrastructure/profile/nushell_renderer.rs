@@ -0,0 +1,153 @@
+use super::shortcuts::{
+    command_functions, enabled_groups, expand_home, powershell_functions, unsupported_plugins,
+};
+use crate::domain::interfaces::ProfileRenderer;
+use crate::domain::{ProfileConfig, ProfileScript, StartupFile};
+
+const GENERATED: &str =
+    "# Generated by setup_powershell; changes inside this block are overwritten\n\n";
+
+/// Nushell double-quoted string literal
+fn nu_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', r"\\").replace('"', "\\\""))
+}
+
+/// Renders managed blocks of Nushell's `env.nu` and `config.nu`
+///
+/// `source` needs its file when `config.nu` is parsed, so `env.nu` writes the
+/// Oh-My-Posh init script and `config.nu` loads it.
+#[derive(Debug, Default)]
+pub struct NushellRenderer;
+
+impl NushellRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn env(&self, config: &ProfileConfig) -> String {
+        let mut content = format!(
+            r#"{}# --- Oh-My-Posh prompt theme, loaded by config.nu ---
+let omp_init = ($nu.home-path | path join ".oh-my-posh.nu")
+if (which oh-my-posh | is-empty) {{
+    "" | save --force $omp_init
+}} else {{
+    let themes = ($env.POSH_THEMES_PATH? | default ($nu.home-path | path join ".cache" "oh-my-posh" "themes"))
+    let theme = ($themes | path join {})
+    if ($theme | path exists) {{
+        oh-my-posh init nu --config $theme --print | save --force $omp_init
+    }} else {{
+        oh-my-posh init nu --print | save --force $omp_init
+    }}
+}}
+"#,
+            GENERATED,
+            nu_quote(&format!("{}.omp.json", config.theme))
+        );
+
+        for group in enabled_groups(config) {
+            if group.env.is_empty() && group.path.is_empty() {
+                continue;
+            }
+            content.push_str(&format!("\n# --- {} ---\n", group.name));
+            for (name, value) in &group.env {
+                content.push_str(&format!("$env.{} = {}\n", name, nu_quote(value)));
+            }
+            for dir in &group.path {
+                content.push_str(&format!(
+                    "$env.PATH = ($env.PATH | prepend {})\n",
+                    nu_quote(&expand_home(dir))
+                ));
+            }
+        }
+
+        content
+    }
+
+    fn config(&self, config: &ProfileConfig) -> String {
+        let mut content = format!(
+            "{}# --- Oh-My-Posh prompt theme ---\nsource ~/.oh-my-posh.nu\n",
+            GENERATED
+        );
+
+        // Aliases and functions; environment settings live in env.nu
+        for group in enabled_groups(config) {
+            let functions: Vec<_> = command_functions(group).collect();
+            if group.aliases.is_empty() && functions.is_empty() {
+                continue;
+            }
+            content.push_str(&format!("\n# --- {} ---\n", group.name));
+            for (name, target) in &group.aliases {
+                content.push_str(&format!("alias {} = {}\n", name, target.posix()));
+            }
+            for (name, command) in functions {
+                content.push_str(&format!(
+                    "def --wrapped {} [...args] {{ {} ...$args }}\n",
+                    name, command
+                ));
+            }
+        }
+
+        content
+    }
+}
+
+impl ProfileRenderer for NushellRenderer {
+    fn shell_name(&self) -> &str {
+        "Nushell"
+    }
+
+    fn render(&self, config: &ProfileConfig) -> Vec<ProfileScript> {
+        vec![
+            ProfileScript {
+                file: StartupFile::Config("nushell/env.nu".into()),
+                content: self.env(config),
+            },
+            ProfileScript {
+                file: StartupFile::Config("nushell/config.nu".into()),
+                content: self.config(config),
+            },
+        ]
+    }
+
+    /// Nushell shows history hints on its own, covering PSReadLine
+    fn unsupported(&self, config: &ProfileConfig) -> Vec<String> {
+        let mut unsupported = unsupported_plugins(config, self.shell_name(), &["PSReadLine"]);
+        unsupported.extend(powershell_functions(config));
+        unsupported
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_env_and_config() {
+        let config = ProfileConfig::builder()
+            .theme("my \"theme\"".to_string())
+            .plugins(vec!["PSReadLine".to_string(), "PSFzf".to_string()])
+            .build();
+        let renderer = NushellRenderer::new();
+        let scripts = renderer.render(&config);
+
+        assert_eq!(
+            scripts[0].file,
+            StartupFile::Config("nushell/env.nu".into())
+        );
+        assert!(scripts[0]
+            .content
+            .contains(r#"path join "my \"theme\".omp.json""#));
+        assert!(scripts[1].content.contains("source ~/.oh-my-posh.nu\n"));
+        assert!(scripts[1]
+            .content
+            .contains("def --wrapped gpush [...args] { git push ...$args }\n"));
+        assert!(scripts[1]
+            .content
+            .contains("def --wrapped gs [...args] { git status ...$args }\n"));
+        assert!(scripts[1].content.contains("alias ll = ls -l\n"));
+        assert_eq!(
+            renderer.unsupported(&config),
+            vec!["PSFzf has no Nushell equivalent and was skipped"]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use super::shortcuts::{
+    command_functions, enabled_groups, expand_home, powershell_functions, unsupported_plugins,
+};
+use crate::domain::interfaces::ProfileRenderer;
+use crate::domain::{ProfileConfig, ProfileScript, StartupFile};
+
+/// POSIX shell single-quoted string literal
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r#"'\''"#))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PosixShell {
+    Bash,
+    Zsh,
+}
+
+/// Renders the managed block of `~/.bashrc` or `~/.zshrc`
+#[derive(Debug)]
+pub struct PosixRenderer {
+    shell: PosixShell,
+}
+
+impl PosixRenderer {
+    pub fn bash() -> Self {
+        Self {
+            shell: PosixShell::Bash,
+        }
+    }
+
+    pub fn zsh() -> Self {
+        Self {
+            shell: PosixShell::Zsh,
+        }
+    }
+
+    fn content(&self, config: &ProfileConfig) -> String {
+        let shell = self.shell_name();
+        let mut content = String::from(
+            "# Generated by setup_powershell; changes inside this block are overwritten\n\n",
+        );
+
+        // Oh-My-Posh theme
+        let theme = format!(
+            "\"${{POSH_THEMES_PATH:-$HOME/.cache/oh-my-posh/themes}}\"/{}",
+            sh_quote(&format!("{}.omp.json", config.theme))
+        );
+        content.push_str(&format!(
+            r#"# --- Oh-My-Posh prompt theme ---
+if command -v oh-my-posh >/dev/null 2>&1; then
+    if [ -f {theme} ]; then
+        eval "$(oh-my-posh init {shell} --config {theme})"
+    else
+        eval "$(oh-my-posh init {shell})"
+    fi
+fi
+
+"#,
+            theme = theme,
+            shell = shell
+        ));
+
+        // fzf key bindings stand in for PSFzf
+        if config.plugins.iter().any(|plugin| plugin == "PSFzf") {
+            let bindings = match self.shell {
+                PosixShell::Bash => "eval \"$(fzf --bash)\"",
+                PosixShell::Zsh => "source <(fzf --zsh)",
+            };
+            content.push_str(&format!(
+                r#"# --- fzf key bindings (Ctrl+T, Ctrl+R, Alt+C) ---
+if command -v fzf >/dev/null 2>&1; then
+    {}
+fi
+
+"#,
+                bindings
+            ));
+        }
+
+        // Aliases, functions and environment
+        for group in enabled_groups(config) {
+            content.push_str(&format!("# --- {} ---\n", group.name));
+            // Functions replace any aliases of the same name, e.g. from oh-my-zsh
+            let functions: Vec<_> = command_functions(group).collect();
+            if !functions.is_empty() {
+                let names: Vec<&str> = functions.iter().map(|(name, _)| *name).collect();
+                content.push_str(&format!("unalias {} 2>/dev/null\n", names.join(" ")));
+            }
+            for (name, target) in &group.aliases {
+                content.push_str(&format!("alias {}={}\n", name, sh_quote(target.posix())));
+            }
+            for (name, command) in functions {
+                content.push_str(&format!("function {} {{ {} \"$@\"; }}\n", name, command));
+            }
+            for (name, value) in &group.env {
+                content.push_str(&format!("export {}={}\n", name, sh_quote(value)));
+            }
+            for dir in &group.path {
+                content.push_str(&format!(
+                    "export PATH={}:\"$PATH\"\n",
+                    sh_quote(&expand_home(dir))
+                ));
+            }
+            content.push('\n');
+        }
+
+        content
+    }
+}
+
+impl ProfileRenderer for PosixRenderer {
+    fn shell_name(&self) -> &str {
+        match self.shell {
+            PosixShell::Bash => "bash",
+            PosixShell::Zsh => "zsh",
+        }
+    }
+
+    fn render(&self, config: &ProfileConfig) -> Vec<ProfileScript> {
+        let file = match self.shell {
+            PosixShell::Bash => ".bashrc",
+            PosixShell::Zsh => ".zshrc",
+        };
+        vec![ProfileScript {
+            file: StartupFile::Home(file.into()),
+            content: self.content(config),
+        }]
+    }
+
+    fn unsupported(&self, config: &ProfileConfig) -> Vec<String> {
+        let mut unsupported = unsupported_plugins(config, self.shell_name(), &["PSFzf"]);
+        unsupported.extend(powershell_functions(config));
+        unsupported
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AliasTarget, FunctionDef, ShortcutGroup};
+
+    #[test]
+    fn test_renders_theme_fzf_and_shortcuts() {
+        let config = ProfileConfig::builder()
+            .theme("it's".to_string())
+            .plugins(vec!["PSFzf".to_string()])
+            .build();
+
+        let bash = &PosixRenderer::bash().render(&config)[0];
+        assert_eq!(bash.file, StartupFile::Home(".bashrc".into()));
+        assert!(bash
+            .content
+            .contains("oh-my-posh init bash --config \"${POSH_THEMES_PATH:-$HOME/.cache/oh-my-posh/themes}\"/'it'\\''s.omp.json'"));
+        assert!(bash.content.contains("eval \"$(fzf --bash)\""));
+        assert!(bash
+            .content
+            .contains("function gcom { git commit \"$@\"; }\n"));
+
+        let zsh = &PosixRenderer::zsh().render(&config)[0];
+        assert_eq!(zsh.file, StartupFile::Home(".zshrc".into()));
+        assert!(zsh.content.contains("eval \"$(oh-my-posh init zsh)\""));
+        assert!(zsh.content.contains("source <(fzf --zsh)"));
+        assert!(PosixRenderer::zsh().unsupported(&config).is_empty());
+
+        let no_aliases = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec![])
+            .groups(vec![])
+            .build();
+        let content = &PosixRenderer::bash().render(&no_aliases)[0].content;
+        assert!(!content.contains("alias") && !content.contains("fzf"));
+    }
+
+    #[test]
+    fn test_renders_custom_group() {
+        let mut group = ShortcutGroup::new("kube");
+        group
+            .aliases
+            .insert("k".to_string(), AliasTarget::new("kubectl"));
+        group.functions.insert(
+            "kctx".to_string(),
+            FunctionDef::command("kubectl config use-context"),
+        );
+        group.functions.insert(
+            "kwatch".to_string(),
+            FunctionDef::Body {
+                body: "kubectl get pods -w @args".to_string(),
+            },
+        );
+        group
+            .env
+            .insert("KUBECONFIG".to_string(), "~/.kube/work".to_string());
+        group.path.push("/opt/kube/bin".to_string());
+        group.path.push("~/.krew/bin".to_string());
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec![])
+            .groups(vec![ShortcutGroup::listing(), group])
+            .build();
+
+        let renderer = PosixRenderer::bash();
+        let content = &renderer.render(&config)[0].content;
+        assert!(content.contains("# --- listing ---\nalias la='ls -la'\nalias ll='ls -l'\n"));
+        assert!(content.contains(
+            "# --- kube ---\nunalias kctx 2>/dev/null\nalias k='kubectl'\nfunction kctx { kubectl config use-context \"$@\"; }\n"
+        ));
+        assert!(content
+            .contains("export KUBECONFIG='~/.kube/work'\nexport PATH='/opt/kube/bin':\"$PATH\"\n"));
+        let krew = dirs::home_dir().unwrap().join(".krew/bin");
+        assert!(content.contains(&format!("export PATH='{}':\"$PATH\"\n", krew.display())));
+        assert!(!content.contains("kwatch"));
+        assert_eq!(
+            renderer.unsupported(&config),
+            vec!["function kwatch of group kube is PowerShell code and was skipped"]
+        );
+    }
+}
@@ -0,0 +1,565 @@
+use async_trait::async_trait;
+use std::sync::Mutex;
+use tracing::debug;
+
+use super::shortcuts::{enabled_groups, expand_home};
+use crate::domain::interfaces::{ProfileRenderer, ShellQuery, ShellRunner};
+use crate::domain::{
+    quote, ColorToken, FunctionDef, KeyAction, ModuleLoading, PSReadLineConfig, PowerShellEdition,
+    PowerShellTarget, PredictionSource, ProfileConfig, ProfileScript, PsCommand, StartupFile,
+};
+
+/// Reports the edition and newest installed PSReadLine as a [`PowerShellTarget`]
+const TARGET_QUERY: &str = "[pscustomobject]@{
+    Edition = if ($PSVersionTable.PSEdition -eq 'Core') { 'Core' } else { 'Desktop' }
+    PSReadLine = Get-Module PSReadLine -ListAvailable | Sort-Object Version -Descending |
+        Select-Object -First 1 | ForEach-Object { \"$($_.Version)\" }
+}";
+
+/// PSReadLine release that added `-PredictionSource` and the `InlinePrediction` color
+const PREDICTION_SOURCE_SINCE: &str = "2.1.0";
+/// PSReadLine release that added `-PredictionViewStyle`, plugin predictions and list colors
+const PREDICTION_VIEW_SINCE: &str = "2.2.0";
+/// PSReadLine release that added the list view tooltip color
+const TOOLTIP_COLOR_SINCE: &str = "2.3.0";
+
+/// PSReadLine version a color token needs beyond 2.0, the version bundled with
+/// Windows PowerShell 5.1
+fn color_since(token: ColorToken) -> Option<&'static str> {
+    match token {
+        ColorToken::InlinePrediction => Some(PREDICTION_SOURCE_SINCE),
+        ColorToken::ListPrediction | ColorToken::ListPredictionSelected => {
+            Some(PREDICTION_VIEW_SINCE)
+        }
+        ColorToken::ListPredictionTooltip => Some(TOOLTIP_COLOR_SINCE),
+        _ => None,
+    }
+}
+
+/// Renders the PowerShell profile (`$PROFILE`)
+///
+/// Options the target's PSReadLine lacks are left out; when its version is
+/// unknown they are guarded by a version check at startup instead.
+#[derive(Debug, Default)]
+pub struct PowerShellRenderer {
+    target: Mutex<PowerShellTarget>,
+}
+
+impl PowerShellRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate for the given host instead of PowerShell 7 with an unknown
+    /// PSReadLine; [`detect`](ProfileRenderer::detect) replaces it
+    pub fn with_target(self, target: PowerShellTarget) -> Self {
+        *self.target.lock().unwrap() = target;
+        self
+    }
+
+    fn target(&self) -> PowerShellTarget {
+        self.target.lock().unwrap().clone()
+    }
+
+    /// Detect the edition and PSReadLine version of the shell's host
+    pub async fn detect_target(shell: &dyn ShellRunner) -> PowerShellTarget {
+        match shell.query_as::<PowerShellTarget>(TARGET_QUERY).await {
+            Ok(mut targets) if !targets.is_empty() => targets.remove(0),
+            _ => PowerShellTarget::default(),
+        }
+    }
+
+    /// Eager imports, the idle-time import block and on-demand stubs
+    fn modules_section(&self, config: &ProfileConfig) -> String {
+        let mut eager = String::new();
+        let mut idle = String::new();
+        let mut on_demand = String::new();
+        for plugin in &config.plugins {
+            let available = PsCommand::new("Get-Module")
+                .switch("ListAvailable")
+                .param("Name", plugin)
+                .to_script();
+            match config.loading(plugin) {
+                ModuleLoading::Eager => {
+                    let import = PsCommand::new("Import-Module").arg(plugin).to_script();
+                    eager += &if plugin == "PSReadLine" || plugin == "posh-git" {
+                        format!("{}\n", import)
+                    } else {
+                        format!("if ({}) {{ {} }}\n", available, import)
+                    };
+                }
+                ModuleLoading::Idle => {
+                    // Modules imported from an event action need -Global to reach the session
+                    let import = PsCommand::new("Import-Module")
+                        .arg(plugin)
+                        .switch("Global")
+                        .to_script();
+                    idle += &format!("    if ({}) {{ {} }}\n", available, import);
+                }
+                ModuleLoading::OnDemand(commands) => {
+                    let stubs = PsCommand::new("Remove-Item")
+                        .list(
+                            "LiteralPath",
+                            commands.iter().map(|c| format!("Function:\\{}", c)),
+                        )
+                        .param("ErrorAction", "SilentlyContinue")
+                        .to_script();
+                    let import = PsCommand::new("Import-Module")
+                        .arg(plugin)
+                        .switch("Global")
+                        .to_script();
+                    for command in &commands {
+                        on_demand += &format!(
+                            "function {} {{\n    {}\n    {}\n    & {} @args\n}}\n",
+                            command,
+                            stubs,
+                            import,
+                            quote(command)
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut lines = format!("# --- Import Modules ---\n{}\n", eager);
+        if !idle.is_empty() {
+            lines += &format!(
+                "# --- Deferred Modules ---\n\
+                 # Imported once the shell is idle after the first prompt\n\
+                 $null = Register-EngineEvent -SourceIdentifier PowerShell.OnIdle -MaxTriggerCount 1 -Action {{\n\
+                 {}}}\n\n",
+                idle
+            );
+        }
+        if !on_demand.is_empty() {
+            lines += &format!(
+                "# --- On-demand Modules ---\n\
+                 # Each stub imports its module on first use and hands over to the real command\n\
+                 {}\n",
+                on_demand
+            );
+        }
+        lines
+    }
+
+    /// Aliases, functions and environment settings of the enabled groups
+    fn shortcuts_section(&self, config: &ProfileConfig) -> String {
+        let mut lines = String::new();
+        for group in enabled_groups(config) {
+            lines += &format!("# --- {} ---\n", group.name);
+            for (name, target) in &group.aliases {
+                // Set-Alias only names a command; anything with arguments needs a function
+                lines += &if target.powershell.contains(char::is_whitespace) {
+                    format!("function {} {{ {} @args }}\n", name, target.powershell)
+                } else {
+                    format!(
+                        "{}\n",
+                        PsCommand::new("Set-Alias")
+                            .param("Name", name)
+                            .param("Value", &target.powershell)
+                            .to_script()
+                    )
+                };
+            }
+            for (name, function) in &group.functions {
+                lines += &match function {
+                    FunctionDef::Command { command } => {
+                        format!("function {} {{ {} @args }}\n", name, command)
+                    }
+                    FunctionDef::Body { body } => {
+                        format!("function {} {{\n{}\n}}\n", name, body.trim_end())
+                    }
+                    FunctionDef::Script { script } => format!(
+                        "function {} {{ & {} @args }}\n",
+                        name,
+                        quote(&script.to_string_lossy())
+                    ),
+                };
+            }
+            for (name, value) in &group.env {
+                lines += &format!("$env:{} = {}\n", name, quote(value));
+            }
+            for dir in &group.path {
+                lines += &format!(
+                    "$env:PATH = {} + [IO.Path]::PathSeparator + $env:PATH\n",
+                    quote(&expand_home(dir))
+                );
+            }
+            lines.push('\n');
+        }
+        lines
+    }
+
+    /// `Set-PSReadLineOption` and `Set-PSReadLineKeyHandler` calls for `psreadline`
+    ///
+    /// Enum values are written by their variant names, which match PSReadLine's.
+    fn psreadline_section(&self, psreadline: &PSReadLineConfig) -> String {
+        let option = PsCommand::new("Set-PSReadLineOption");
+        let param = |name: &str, value: Option<String>| {
+            value.map(|value| option.clone().param(name, value))
+        };
+        let flag =
+            |name: &str, value: Option<bool>| value.map(|value| option.clone().flag(name, value));
+        let named = |value: &dyn std::fmt::Debug| format!("{:?}", value);
+
+        let options = [
+            param("EditMode", psreadline.edit_mode.map(|v| named(&v))),
+            param("BellStyle", psreadline.bell_style.map(|v| named(&v))),
+            flag("HistoryNoDuplicates", psreadline.history_no_duplicates),
+            flag(
+                "HistorySearchCursorMovesToEnd",
+                psreadline.history_search_cursor_moves_to_end,
+            ),
+            param(
+                "MaximumHistoryCount",
+                psreadline
+                    .maximum_history_count
+                    .map(|count| count.to_string()),
+            ),
+            param(
+                "HistorySaveStyle",
+                psreadline.history_save_style.map(|v| named(&v)),
+            ),
+        ];
+        let mut lines = String::new();
+        for command in options.into_iter().flatten() {
+            lines += &format!("{}\n", command.to_script());
+        }
+
+        if let Some(source) = psreadline.prediction_source {
+            let since = match source {
+                PredictionSource::None | PredictionSource::History => PREDICTION_SOURCE_SINCE,
+                PredictionSource::Plugin | PredictionSource::HistoryAndPlugin => {
+                    PREDICTION_VIEW_SINCE
+                }
+            };
+            let line = option.clone().param("PredictionSource", named(&source));
+            lines += &self.psreadline_option(&line.to_script(), since);
+        }
+        if let Some(style) = psreadline.prediction_view_style {
+            let line = option.clone().param("PredictionViewStyle", named(&style));
+            lines += &self.psreadline_option(&line.to_script(), PREDICTION_VIEW_SINCE);
+        }
+
+        // Colors every PSReadLine 2.x accepts go in one table
+        let (newer, common): (Vec<_>, Vec<_>) = psreadline
+            .colors
+            .iter()
+            .partition(|(token, _)| color_since(**token).is_some());
+        if !common.is_empty() {
+            lines += "\n# Syntax colors\nSet-PSReadLineOption -Colors @{\n";
+            for (token, color) in common {
+                lines += &format!("    {:?} = {}\n", token, quote(color));
+            }
+            lines += "}\n";
+        }
+        for (token, color) in newer {
+            let line = format!(
+                "Set-PSReadLineOption -Colors @{{ {:?} = {} }}",
+                token,
+                quote(color)
+            );
+            lines += &self.psreadline_option(&line, color_since(*token).unwrap_or_default());
+        }
+
+        if !psreadline.key_handlers.is_empty() {
+            lines += "\n# Key handlers\n";
+        }
+        for handler in &psreadline.key_handlers {
+            let mut command =
+                PsCommand::new("Set-PSReadLineKeyHandler").param("Chord", &handler.chord);
+            if let Some(description) = &handler.description {
+                command = command.param("BriefDescription", description);
+            }
+            match &handler.action {
+                KeyAction::Function { function } => {
+                    lines += &format!("{}\n", command.param("Function", function).to_script());
+                }
+                KeyAction::Script { script } => {
+                    lines += &format!(
+                        "{} -ScriptBlock {{\n{}\n}}\n",
+                        command.to_script(),
+                        script.trim_end()
+                    );
+                }
+            }
+        }
+
+        if lines.is_empty() {
+            return lines;
+        }
+        format!("# --- PSReadLine Settings ---\n{}", lines)
+    }
+
+    /// `line` if the target's PSReadLine supports it, guarded when unknown
+    fn psreadline_option(&self, line: &str, since: &str) -> String {
+        match self.target().psreadline_at_least(since) {
+            Some(true) => format!("{}\n", line),
+            Some(false) => String::new(),
+            None => format!(
+                "if ((Get-Module PSReadLine).Version -ge [version]{}) {{ {} }}\n",
+                quote(since),
+                line
+            ),
+        }
+    }
+
+    fn content(&self, config: &ProfileConfig) -> String {
+        let title = match self.target().edition {
+            PowerShellEdition::Core => "Modern PowerShell 7 Profile",
+            PowerShellEdition::Desktop => "Windows PowerShell 5.1 Profile",
+        };
+        let rule = "=".repeat(title.len());
+        let mut content = format!("# {}\n# {}\n# {}\n\n", rule, title, rule);
+
+        // Import modules
+        content.push_str(&self.modules_section(config));
+
+        // Oh-My-Posh theme
+        content.push_str(&format!(
+            r#"# --- Oh-My-Posh prompt theme ---
+if (Get-Command oh-my-posh -ErrorAction SilentlyContinue) {{
+    $themes = if ($env:POSH_THEMES_PATH) {{ $env:POSH_THEMES_PATH }} else {{ Join-Path $HOME '.cache/oh-my-posh/themes' }}
+    $configPath = Join-Path $themes {}
+    if (Test-Path $configPath) {{
+        oh-my-posh init pwsh --config $configPath | Invoke-Expression
+    }} else {{
+        oh-my-posh init pwsh | Invoke-Expression
+    }}
+}}
+
+"#,
+            quote(&format!("{}.omp.json", config.theme))
+        ));
+
+        // PSReadLine settings
+        content.push_str(&self.psreadline_section(&config.psreadline));
+
+        // Aliases, functions and environment
+        content.push_str(&self.shortcuts_section(config));
+
+        // Environment
+        content.push_str(
+            r#"# --- Environment ---
+$env:POSH_GIT_ENABLED = $true
+"#,
+        );
+
+        content
+    }
+}
+
+#[async_trait]
+impl ProfileRenderer for PowerShellRenderer {
+    fn shell_name(&self) -> &str {
+        "PowerShell"
+    }
+
+    async fn detect(&self, shell: &dyn ShellRunner) {
+        let target = Self::detect_target(shell).await;
+        debug!("Profile target: {:?}", target);
+        *self.target.lock().unwrap() = target;
+    }
+
+    fn render(&self, config: &ProfileConfig) -> Vec<ProfileScript> {
+        let content = match self.target().edition {
+            PowerShellEdition::Core => self.content(config),
+            // Windows PowerShell reads scripts without a BOM in the ANSI code page
+            PowerShellEdition::Desktop => {
+                format!("\u{feff}{}", self.content(config).replace('\n', "\r\n"))
+            }
+        };
+        vec![ProfileScript {
+            file: StartupFile::PowerShellProfile,
+            content,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AliasTarget, ShortcutGroup};
+    use crate::infrastructure::shell::FakeShell;
+
+    #[test]
+    fn test_renders_shortcut_groups() {
+        let mut tools = ShortcutGroup::new("tools");
+        tools
+            .aliases
+            .insert("k".to_string(), AliasTarget::new("kubectl"));
+        tools.functions.insert(
+            "kwatch".to_string(),
+            FunctionDef::Body {
+                body: "kubectl get pods -w @args".to_string(),
+            },
+        );
+        tools.functions.insert(
+            "deploy".to_string(),
+            FunctionDef::Script {
+                script: "/opt/it's/deploy.ps1".into(),
+            },
+        );
+        tools
+            .env
+            .insert("EDITOR".to_string(), "code --wait".to_string());
+        tools.path.push("~/bin".to_string());
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec![])
+            .groups(vec![
+                ShortcutGroup::listing(),
+                ShortcutGroup::git().enabled(false),
+                tools,
+            ])
+            .build();
+        let content = &PowerShellRenderer::new().render(&config)[0].content;
+
+        assert!(content.contains("Set-Alias -Name 'll' -Value 'Get-ChildItem'\n"));
+        assert!(content.contains("function la { Get-ChildItem -Force @args }\n"));
+        assert!(!content.contains("function gs"));
+        assert!(content.contains("function kwatch {\nkubectl get pods -w @args\n}\n"));
+        assert!(content.contains("function deploy { & '/opt/it''s/deploy.ps1' @args }\n"));
+        assert!(content.contains("$env:EDITOR = 'code --wait'\n"));
+        let bin = dirs::home_dir().unwrap().join("bin");
+        assert!(content.contains(&format!(
+            "$env:PATH = '{}' + [IO.Path]::PathSeparator + $env:PATH\n",
+            bin.display()
+        )));
+    }
+
+    #[test]
+    fn test_guards_optional_imports() {
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec!["PSReadLine".to_string(), "Terminal-Icons".to_string()])
+            .module_loading(
+                [("Terminal-Icons".to_string(), ModuleLoading::Eager)]
+                    .into_iter()
+                    .collect(),
+            )
+            .build();
+        let scripts = PowerShellRenderer::new().render(&config);
+
+        assert_eq!(scripts[0].file, StartupFile::PowerShellProfile);
+        let content = &scripts[0].content;
+        assert!(content.contains("\nImport-Module 'PSReadLine'\n"));
+        assert!(content.contains(
+            "if (Get-Module -ListAvailable -Name 'Terminal-Icons') { Import-Module 'Terminal-Icons' }"
+        ));
+        assert!(content.contains(
+            "$themes = if ($env:POSH_THEMES_PATH) { $env:POSH_THEMES_PATH } else { Join-Path $HOME '.cache/oh-my-posh/themes' }\n    $configPath = Join-Path $themes 'paradox.omp.json'"
+        ));
+        assert!(content.contains(
+            "if ((Get-Module PSReadLine).Version -ge [version]'2.2.0') { Set-PSReadLineOption -PredictionViewStyle 'InlineView' }"
+        ));
+    }
+
+    #[test]
+    fn test_defers_slow_modules() {
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(
+                ["PSReadLine", "Terminal-Icons", "PSFzf", "z"]
+                    .map(String::from)
+                    .to_vec(),
+            )
+            .module_loading(
+                [(
+                    "z".to_string(),
+                    ModuleLoading::OnDemand(vec!["z".to_string()]),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .build();
+        let content = &PowerShellRenderer::new().render(&config)[0].content;
+
+        assert!(content.contains(
+            "# --- Import Modules ---\nImport-Module 'PSReadLine'\nif (Get-Module -ListAvailable -Name 'PSFzf') { Import-Module 'PSFzf' }\n\n"
+        ));
+        assert!(content.contains(
+            "-Action {\n    if (Get-Module -ListAvailable -Name 'Terminal-Icons') { Import-Module 'Terminal-Icons' -Global }\n"
+        ));
+        assert!(content.contains(
+            "function z {\n    Remove-Item -LiteralPath 'Function:\\z' -ErrorAction 'SilentlyContinue'\n    Import-Module 'z' -Global\n    & 'z' @args\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_windows_powershell_target() {
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec![])
+            .build();
+        let target = PowerShellTarget::new(PowerShellEdition::Desktop).with_psreadline("2.0.0");
+        let content = &PowerShellRenderer::new()
+            .with_target(target)
+            .render(&config)[0]
+            .content;
+
+        assert!(content.starts_with("\u{feff}# ="));
+        assert!(!content.replace("\r\n", "").contains('\n'));
+        assert!(!content.contains("Prediction"));
+        assert!(content.contains("Set-PSReadLineOption -EditMode 'Windows'\r\n"));
+
+        let target = PowerShellTarget::new(PowerShellEdition::Core).with_psreadline("2.3.5");
+        let content = &PowerShellRenderer::new()
+            .with_target(target)
+            .render(&config)[0]
+            .content;
+        assert!(content.contains("\nSet-PSReadLineOption -PredictionViewStyle 'InlineView'\n"));
+    }
+
+    #[test]
+    fn test_renders_psreadline_config() {
+        let psreadline: PSReadLineConfig = serde_json::from_str(
+            r#"{
+                "edit_mode": "Emacs",
+                "history_no_duplicates": true,
+                "maximum_history_count": 8192,
+                "colors": { "Comment": "DarkGray", "ListPrediction": "Green" },
+                "key_handlers": [
+                    { "chord": "Ctrl+x,Ctrl+e", "script": "Invoke-Item $PROFILE", "description": "Open profile" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let config = ProfileConfig::builder()
+            .theme("paradox".to_string())
+            .plugins(vec![])
+            .psreadline(psreadline)
+            .build();
+        let target = PowerShellTarget::new(PowerShellEdition::Core).with_psreadline("2.1.0");
+        let content = &PowerShellRenderer::new()
+            .with_target(target)
+            .render(&config)[0]
+            .content;
+
+        assert!(content.contains("Set-PSReadLineOption -EditMode 'Emacs'\n"));
+        assert!(content.contains("Set-PSReadLineOption -HistoryNoDuplicates:$true\n"));
+        assert!(content.contains("Set-PSReadLineOption -MaximumHistoryCount '8192'\n"));
+        assert!(content.contains("Set-PSReadLineOption -Colors @{\n    Comment = 'DarkGray'\n}\n"));
+        assert!(!content.contains("ListPrediction"));
+        assert!(content.contains(
+            "Set-PSReadLineKeyHandler -Chord 'Ctrl+x,Ctrl+e' -BriefDescription 'Open profile' -ScriptBlock {\nInvoke-Item $PROFILE\n}\n"
+        ));
+        assert!(!content.contains("PredictionSource"));
+    }
+
+    #[tokio::test]
+    async fn test_detects_target() {
+        let shell = FakeShell::new().on_query(
+            "PSEdition",
+            serde_json::json!([{ "Edition": "Desktop", "PSReadLine": "2.0.0" }]),
+        );
+        let target = PowerShellRenderer::detect_target(&shell).await;
+        assert_eq!(
+            target,
+            PowerShellTarget::new(PowerShellEdition::Desktop).with_psreadline("2.0.0")
+        );
+
+        let target = PowerShellRenderer::detect_target(&FakeShell::new()).await;
+        assert_eq!(target, PowerShellTarget::default());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::domain::{FunctionDef, ProfileConfig, ShortcutGroup};
+
+/// Warnings for selected plugins a shell has no counterpart for
+pub fn unsupported_plugins(config: &ProfileConfig, shell: &str, supported: &[&str]) -> Vec<String> {
+    config
+        .plugins
+        .iter()
+        .filter(|plugin| !supported.contains(&plugin.as_str()))
+        .map(|plugin| format!("{} has no {} equivalent and was skipped", plugin, shell))
+        .collect()
+}
+
+/// Warnings for functions written in PowerShell, which other shells skip
+pub fn powershell_functions(config: &ProfileConfig) -> Vec<String> {
+    enabled_groups(config)
+        .flat_map(|group| {
+            group
+                .functions
+                .iter()
+                .filter(|(_, function)| !matches!(function, FunctionDef::Command { .. }))
+                .map(|(name, _)| {
+                    format!(
+                        "function {} of group {} is PowerShell code and was skipped",
+                        name, group.name
+                    )
+                })
+        })
+        .collect()
+}
+
+/// `dir` with a leading `~` replaced by the home directory, since the quoting
+/// of the generated profiles keeps it literal
+pub fn expand_home(dir: &str) -> String {
+    match (dir.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            format!("{}{}", home.display(), rest)
+        }
+        _ => dir.to_string(),
+    }
+}
+
+/// Groups switched on in `config`
+pub fn enabled_groups(config: &ProfileConfig) -> impl Iterator<Item = &ShortcutGroup> {
+    config.groups.iter().filter(|group| group.enabled)
+}
+
+/// Native command lines of the group's portable functions
+pub fn command_functions(group: &ShortcutGroup) -> impl Iterator<Item = (&str, &str)> {
+    group
+        .functions
+        .iter()
+        .filter_map(|(name, function)| match function {
+            FunctionDef::Command { command } => Some((name.as_str(), command.as_str())),
+            _ => None,
+        })
+}
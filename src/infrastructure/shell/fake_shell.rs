@@ -0,0 +1,275 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::fixture::{load_fixture, CallKind, Reply};
+use crate::domain::interfaces::ShellRunner;
+
+/// How a rule selects commands
+#[derive(Debug, Clone)]
+enum Matcher {
+    Exact(String),
+    Contains(String),
+}
+
+impl Matcher {
+    fn matches(&self, command: &str) -> bool {
+        match self {
+            Matcher::Exact(expected) => command == expected,
+            Matcher::Contains(fragment) => command.contains(fragment.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    kind: CallKind,
+    matcher: Matcher,
+    reply: Reply,
+    /// Replayed interactions answer a single call each, in recorded order
+    once: bool,
+}
+
+/// Scriptable `ShellRunner` answering commands without starting PowerShell
+///
+/// Rules are checked in the order they were added; a command no rule matches
+/// fails. Every call is kept for assertions:
+///
+/// ```
+/// # use setup_powershell::infrastructure::FakeShell;
+/// let shell = FakeShell::new()
+///     .on_run("Install-Module", "")
+///     .on_query("Get-Module", serde_json::json!([]));
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeShell {
+    name: String,
+    rules: Mutex<Vec<Rule>>,
+    calls: Mutex<Vec<(CallKind, String)>>,
+    /// Time every `run` and `query` call takes before answering
+    delay: Duration,
+}
+
+impl FakeShell {
+    pub fn new() -> Self {
+        Self {
+            name: "pwsh".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Answer with the interactions of a fixture file recorded by
+    /// [`RecordingShell`](super::RecordingShell), each exactly once and in order
+    pub fn replay(path: &Path) -> Result<Self> {
+        let rules = load_fixture(path)?
+            .into_iter()
+            .map(|interaction| Rule {
+                kind: interaction.kind,
+                matcher: Matcher::Exact(interaction.command),
+                reply: interaction.reply,
+                once: true,
+            })
+            .collect();
+        let shell = Self::new();
+        *shell.rules.lock().unwrap() = rules;
+        Ok(shell)
+    }
+
+    /// Shell name reported by `shell_name`
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Make every `run` and `query` call take `delay`, e.g. to hit a timeout
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn rule(self, kind: CallKind, matcher: Matcher, reply: Reply) -> Self {
+        self.rules.lock().unwrap().push(Rule {
+            kind,
+            matcher,
+            reply,
+            once: false,
+        });
+        self
+    }
+
+    /// `run` calls containing `fragment` print `output`
+    pub fn on_run(self, fragment: &str, output: impl Into<String>) -> Self {
+        self.rule(
+            CallKind::Run,
+            Matcher::Contains(fragment.to_string()),
+            Reply::Output(output.into()),
+        )
+    }
+
+    /// `query` calls containing `fragment` return `json`
+    pub fn on_query(self, fragment: &str, json: serde_json::Value) -> Self {
+        self.rule(
+            CallKind::Query,
+            Matcher::Contains(fragment.to_string()),
+            Reply::Json(json),
+        )
+    }
+
+    /// `run` and `query` calls containing `fragment` fail with `message`
+    pub fn fail(self, fragment: &str, message: impl Into<String>) -> Self {
+        let message = message.into();
+        self.rule(
+            CallKind::Run,
+            Matcher::Contains(fragment.to_string()),
+            Reply::Error(message.clone()),
+        )
+        .rule(
+            CallKind::Query,
+            Matcher::Contains(fragment.to_string()),
+            Reply::Error(message),
+        )
+    }
+
+    /// Answer `exists(command)`
+    pub fn with_command(self, command: &str, exists: bool) -> Self {
+        self.rule(
+            CallKind::Exists,
+            Matcher::Exact(command.to_string()),
+            Reply::Exists(exists),
+        )
+    }
+
+    /// Commands passed to `run` and `query`, in call order
+    pub fn calls(&self) -> Vec<String> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(kind, _)| *kind != CallKind::Exists)
+            .map(|(_, command)| command.clone())
+            .collect()
+    }
+
+    fn answer(&self, kind: CallKind, command: &str) -> Result<Reply> {
+        self.calls.lock().unwrap().push((kind, command.to_string()));
+
+        let mut rules = self.rules.lock().unwrap();
+        let index = rules
+            .iter()
+            .position(|rule| rule.kind == kind && rule.matcher.matches(command))
+            .ok_or_else(|| anyhow::anyhow!("Unexpected {:?} command: {}", kind, command))?;
+
+        let reply = rules[index].reply.clone();
+        if rules[index].once {
+            rules.remove(index);
+        }
+        match reply {
+            Reply::PowerShellError(record) => Err(record.into()),
+            Reply::Error(message) => Err(anyhow::anyhow!(message)),
+            reply => Ok(reply),
+        }
+    }
+}
+
+#[async_trait]
+impl ShellRunner for FakeShell {
+    async fn run(&self, cmd: &str) -> Result<String> {
+        tokio::time::sleep(self.delay).await;
+        match self.answer(CallKind::Run, cmd)? {
+            Reply::Output(output) => Ok(output),
+            reply => anyhow::bail!("Fixture reply {:?} does not fit run: {}", reply, cmd),
+        }
+    }
+
+    async fn query(&self, cmd: &str) -> Result<serde_json::Value> {
+        tokio::time::sleep(self.delay).await;
+        match self.answer(CallKind::Query, cmd)? {
+            Reply::Json(json) => Ok(json),
+            reply => anyhow::bail!("Fixture reply {:?} does not fit query: {}", reply, cmd),
+        }
+    }
+
+    async fn exists(&self, command: &str) -> bool {
+        matches!(
+            self.answer(CallKind::Exists, command),
+            Ok(Reply::Exists(true))
+        )
+    }
+
+    fn shell_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ModuleInfo, PowerShellError, ShellQuery};
+    use crate::infrastructure::shell::fixture::{save_fixture, Interaction};
+
+    #[tokio::test]
+    async fn test_scripted_replies() {
+        let shell = FakeShell::new()
+            .on_run("$PROFILE", "/home/me/profile.ps1")
+            .on_query(
+                "Get-Module",
+                serde_json::json!([{ "Name": "z", "Version": "1.9.4" }]),
+            )
+            .fail("Install-Module", "No match was found")
+            .with_command("pwsh", true);
+
+        assert_eq!(shell.run("$PROFILE").await.unwrap(), "/home/me/profile.ps1");
+        let modules: Vec<ModuleInfo> = shell.query_as("Get-Module -Name 'z'").await.unwrap();
+        assert_eq!(modules[0].version, "1.9.4");
+        assert!(shell.run("Install-Module -Name 'z'").await.is_err());
+        assert!(shell.run("Remove-Item ~").await.is_err());
+        assert!(shell.exists("pwsh").await);
+        assert!(!shell.exists("powershell").await);
+        assert_eq!(shell.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_replays_fixture_in_order() {
+        let path = std::env::temp_dir().join("setup_powershell_replay.json");
+        let record = PowerShellError {
+            message: "No match was found".to_string(),
+            category: "ObjectNotFound".to_string(),
+            error_id: "NoMatchFoundForCriteria".to_string(),
+            target: "nope".to_string(),
+            exception_type: "System.Exception".to_string(),
+        };
+        save_fixture(
+            &path,
+            &[
+                Interaction {
+                    kind: CallKind::Run,
+                    command: "Get-Date".to_string(),
+                    reply: Reply::Output("first".to_string()),
+                },
+                Interaction {
+                    kind: CallKind::Run,
+                    command: "Get-Date".to_string(),
+                    reply: Reply::Output("second".to_string()),
+                },
+                Interaction {
+                    kind: CallKind::Query,
+                    command: "Find-Module nope".to_string(),
+                    reply: Reply::PowerShellError(record),
+                },
+            ],
+        )
+        .unwrap();
+
+        let shell = FakeShell::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(shell.run("Get-Date").await.unwrap(), "first");
+        assert_eq!(shell.run("Get-Date").await.unwrap(), "second");
+        assert!(shell.run("Get-Date").await.is_err());
+
+        let error = shell.query("Find-Module nope").await.unwrap_err();
+        let record = error.downcast_ref::<PowerShellError>().unwrap();
+        assert_eq!(record.category, "ObjectNotFound");
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::domain::PowerShellError;
+
+/// `ShellRunner` method an interaction went through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallKind {
+    Run,
+    Query,
+    Exists,
+}
+
+/// Result returned for a command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reply {
+    /// Text printed by `run`
+    Output(String),
+    /// Objects returned by `query`
+    Json(serde_json::Value),
+    /// Answer of `exists`
+    Exists(bool),
+    /// Error record raised by the command
+    PowerShellError(PowerShellError),
+    /// Any other failure, by message
+    Error(String),
+}
+
+impl Reply {
+    /// Reply describing the result of a `run` or `query` call
+    pub fn from_result<T: Into<Reply> + Clone>(result: &Result<T>) -> Self {
+        match result {
+            Ok(value) => value.clone().into(),
+            Err(e) => match e.downcast_ref::<PowerShellError>() {
+                Some(record) => Reply::PowerShellError(record.clone()),
+                None => Reply::Error(format!("{:#}", e)),
+            },
+        }
+    }
+}
+
+impl From<String> for Reply {
+    fn from(output: String) -> Self {
+        Reply::Output(output)
+    }
+}
+
+impl From<serde_json::Value> for Reply {
+    fn from(json: serde_json::Value) -> Self {
+        Reply::Json(json)
+    }
+}
+
+/// A command and its reply, as stored in fixture files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub kind: CallKind,
+    pub command: String,
+    pub reply: Reply,
+}
+
+/// Read the interactions recorded in a fixture file
+pub fn load_fixture(path: &Path) -> Result<Vec<Interaction>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid fixture {}", path.display()))
+}
+
+/// Write interactions to a fixture file, replacing its contents
+pub fn save_fixture(path: &Path, interactions: &[Interaction]) -> Result<()> {
+    let content = serde_json::to_string_pretty(interactions)?;
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write fixture {}", path.display()))
+}
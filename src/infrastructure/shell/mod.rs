@@ -1,3 +1,13 @@
+pub mod fake_shell;
+pub mod fixture;
 pub mod powershell_runner;
+pub mod powershell_session;
+pub mod query;
+pub mod recording_shell;
 
-pub use powershell_runner::PowerShellRunner;
+pub use fake_shell::FakeShell;
+pub use fixture::{load_fixture, save_fixture, CallKind, Interaction, Reply};
+pub use powershell_runner::{host_args, PowerShellRunner};
+pub use powershell_session::PowerShellSession;
+pub use query::{parse_query_output, query_script};
+pub use recording_shell::RecordingShell;
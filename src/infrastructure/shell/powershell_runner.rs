@@ -1,14 +1,34 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use colored::Colorize;
+use base64::prelude::*;
 use std::process::Stdio;
+use std::sync::Arc;
 use tokio::process::Command;
+use tracing::debug;
 
+use super::query::{parse_query_output, query_script};
 use crate::domain::interfaces::ShellRunner;
+use crate::infrastructure::process::{run_captured, RunLog};
+
+/// Arguments running `script` without profiles or prompts
+///
+/// The script is passed as `-EncodedCommand` (Base64 of UTF-16LE) so that no
+/// quoting of the process command line can alter it.
+pub fn host_args(script: &str) -> Vec<String> {
+    let utf16: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    vec![
+        "-NoLogo".to_string(),
+        "-NoProfile".to_string(),
+        "-NonInteractive".to_string(),
+        "-EncodedCommand".to_string(),
+        BASE64_STANDARD.encode(utf16),
+    ]
+}
 
 /// PowerShell command runner implementation
 pub struct PowerShellRunner {
     shell: String,
+    log: Option<Arc<RunLog>>,
 }
 
 impl PowerShellRunner {
@@ -19,9 +39,16 @@ impl PowerShellRunner {
             } else {
                 "powershell".to_string()
             },
+            log: None,
         }
     }
 
+    /// Record command output in the run log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.log = Some(log);
+        self
+    }
+
     pub fn pwsh() -> Self {
         Self::new(true)
     }
@@ -29,25 +56,37 @@ impl PowerShellRunner {
     pub fn powershell() -> Self {
         Self::new(false)
     }
+
+    /// Run `script`, describing it in the log by the first line of `cmd`
+    async fn execute(&self, script: &str, cmd: &str) -> Result<String> {
+        debug!("➡ {}", cmd);
+
+        let mut command = Command::new(&self.shell);
+        command
+            .kill_on_drop(true)
+            .args(host_args(script))
+            .stdin(Stdio::null());
+        let description = match cmd.split_once('\n') {
+            Some((first_line, _)) => format!("{} {} ...", self.shell, first_line),
+            None => format!("{} {}", self.shell, cmd),
+        };
+        let output = run_captured(&mut command, &description, self.log.as_deref())
+            .await
+            .context("PowerShell command failed")?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
 }
 
 #[async_trait]
 impl ShellRunner for PowerShellRunner {
     async fn run(&self, cmd: &str) -> Result<String> {
-        println!("{} {}", "➡".blue(), cmd.bright_black());
-
-        let output = Command::new(&self.shell)
-            .args(["-Command", cmd])
-            .output()
-            .await
-            .context("Failed to execute PowerShell command")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Command failed: {}", stderr);
-        }
+        self.execute(cmd, cmd).await
+    }
 
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    async fn query(&self, cmd: &str) -> Result<serde_json::Value> {
+        let output = self.execute(&query_script(cmd), cmd).await?;
+        parse_query_output(&output)
     }
 
     async fn exists(&self, command: &str) -> bool {
@@ -77,4 +116,21 @@ mod tests {
         let runner = PowerShellRunner::powershell();
         assert_eq!(runner.shell_name(), "powershell");
     }
+
+    #[test]
+    fn test_host_args_encode_script() {
+        let args = host_args("'é'");
+
+        assert_eq!(
+            args[..4],
+            [
+                "-NoLogo",
+                "-NoProfile",
+                "-NonInteractive",
+                "-EncodedCommand"
+            ]
+        );
+        let bytes = BASE64_STANDARD.decode(&args[4]).unwrap();
+        assert_eq!(bytes, [0x27, 0, 0xe9, 0, 0x27, 0]);
+    }
 }
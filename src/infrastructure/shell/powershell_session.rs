@@ -0,0 +1,302 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::process::{ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+use tracing::{debug, trace};
+
+use super::powershell_runner::host_args;
+use super::query::{parse_query_output, query_script};
+use crate::domain::interfaces::ShellRunner;
+use crate::infrastructure::process::run_log::failure;
+use crate::infrastructure::process::RunLog;
+
+/// Prefix marking protocol responses among other host output
+const RESPONSE_MARKER: &str = "@@setup_powershell:";
+
+/// Request loop run by the session process
+///
+/// Each request is one line of JSON `{"id":..,"script":".."}`; each response one
+/// marked line of JSON with the script's output, error records and exit code.
+/// A script fails when it throws, writes PowerShell errors or leaves a non-zero
+/// `$LASTEXITCODE`; stderr of native commands alone does not fail it.
+const SERVER_SCRIPT: &str = r#"$ProgressPreference = 'SilentlyContinue'
+[Console]::OutputEncoding = [Text.UTF8Encoding]::new($false)
+$reader = [Console]::In
+while ($null -ne ($line = $reader.ReadLine())) {
+    $request = $line | ConvertFrom-Json
+    $global:LASTEXITCODE = 0
+    $output = ''
+    $errors = @()
+    $code = 0
+    try {
+        $objects = @(& ([scriptblock]::Create($request.script)) 2>&1)
+        $errors = @($objects | Where-Object { $_ -is [System.Management.Automation.ErrorRecord] })
+        $output = $objects | Where-Object { $_ -isnot [System.Management.Automation.ErrorRecord] } |
+            Out-String -Width 4096
+        if ($errors | Where-Object { $_.Exception -isnot [System.Management.Automation.RemoteException] }) {
+            $code = 1
+        }
+        if ($LASTEXITCODE) { $code = $LASTEXITCODE }
+    } catch {
+        $errors += $_
+        $code = 1
+    }
+    $response = @{ id = $request.id; code = $code; stdout = "$output"; stderr = ($errors | Out-String -Width 4096) }
+    [Console]::Out.WriteLine('@@setup_powershell:' + (ConvertTo-Json -InputObject $response -Compress))
+    [Console]::Out.Flush()
+}"#;
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    id: u64,
+    code: i32,
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+}
+
+/// JSON request line, with non-ASCII characters escaped so the console input
+/// encoding of the session does not matter
+fn encode_request(id: u64, script: &str) -> String {
+    let json = serde_json::json!({ "id": id, "script": script }).to_string();
+    let mut line = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            line.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                line.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    line.push('\n');
+    line
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+/// A running session process
+struct SessionProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl SessionProcess {
+    fn spawn(shell: &str) -> Result<Self> {
+        let mut child = Command::new(shell)
+            .kill_on_drop(true)
+            .args(host_args(SERVER_SCRIPT))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to start {} session", shell))?;
+
+        let stdin = child.stdin.take().context("Session stdin unavailable")?;
+        let stdout = child.stdout.take().context("Session stdout unavailable")?;
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Send a script and wait for its response
+    ///
+    /// Yields the process' exit status and any output printed so far instead when
+    /// it exited while running the script, e.g. because the script called `exit`.
+    async fn request(&mut self, id: u64, script: &str) -> Result<Result<Response, Output>> {
+        self.stdin
+            .write_all(encode_request(id, script).as_bytes())
+            .await
+            .context("Failed to send command to PowerShell session")?;
+        self.stdin.flush().await?;
+
+        let mut host_output = String::new();
+        while let Some(line) = self.stdout.next_line().await? {
+            match line.strip_prefix(RESPONSE_MARKER) {
+                Some(json) => {
+                    let response: Response = serde_json::from_str(json)
+                        .context("Invalid response from PowerShell session")?;
+                    if response.id == id {
+                        return Ok(Ok(Response {
+                            stdout: host_output + &response.stdout,
+                            ..response
+                        }));
+                    }
+                }
+                // Write-Host and similar output goes straight to the console
+                None => {
+                    host_output.push_str(&line);
+                    host_output.push('\n');
+                }
+            }
+        }
+
+        let status = self.child.wait().await?;
+        Ok(Err(Output {
+            status,
+            stdout: host_output.into_bytes(),
+            stderr: vec![],
+        }))
+    }
+}
+
+/// `ShellRunner` keeping one PowerShell process open for all commands
+///
+/// Saves a process start per command. The process is started on first use and
+/// restarted when it dies or a command is abandoned midway (timeout, Ctrl-C).
+pub struct PowerShellSession {
+    shell: String,
+    log: Option<Arc<RunLog>>,
+    process: Mutex<Option<SessionProcess>>,
+    next_id: AtomicU64,
+}
+
+impl PowerShellSession {
+    pub fn new(use_pwsh: bool) -> Self {
+        Self {
+            shell: if use_pwsh {
+                "pwsh".to_string()
+            } else {
+                "powershell".to_string()
+            },
+            log: None,
+            process: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Record command output in the run log
+    pub fn with_log(mut self, log: Arc<RunLog>) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    pub fn pwsh() -> Self {
+        Self::new(true)
+    }
+
+    pub fn powershell() -> Self {
+        Self::new(false)
+    }
+
+    /// Run `script` in the session, describing it in the log by the first line of `cmd`
+    async fn execute(&self, script: &str, cmd: &str) -> Result<String> {
+        debug!("➡ {}", cmd);
+        let description = match cmd.split_once('\n') {
+            Some((first_line, _)) => format!("{} session: {} ...", self.shell, first_line),
+            None => format!("{} session: {}", self.shell, cmd),
+        };
+
+        // The process is taken out of the slot while a command runs, so a dropped
+        // request drops (and kills) it and the next command starts a fresh one
+        let mut slot = self.process.lock().await;
+        let alive = slot
+            .take()
+            .and_then(|mut process| process.is_alive().then_some(process));
+        let mut process = match alive {
+            Some(process) => process,
+            None => SessionProcess::spawn(&self.shell)?,
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let output = match process.request(id, script).await? {
+            Ok(response) => {
+                *slot = Some(process);
+                Output {
+                    status: exit_status(response.code),
+                    stdout: response.stdout.into_bytes(),
+                    stderr: response.stderr.into_bytes(),
+                }
+            }
+            Err(exited) => exited,
+        };
+
+        if let Some(log) = &self.log {
+            log.record(&description, &output);
+        }
+        trace!(
+            "{} ({})\n{}{}",
+            description,
+            output.status,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        if !output.status.success() {
+            return Err(failure(&description, &output, self.log.as_deref()))
+                .context("PowerShell command failed");
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+#[async_trait]
+impl ShellRunner for PowerShellSession {
+    async fn run(&self, cmd: &str) -> Result<String> {
+        self.execute(cmd, cmd).await
+    }
+
+    async fn query(&self, cmd: &str) -> Result<serde_json::Value> {
+        let output = self.execute(&query_script(cmd), cmd).await?;
+        parse_query_output(&output)
+    }
+
+    async fn exists(&self, command: &str) -> bool {
+        Command::new(command)
+            .arg("-Version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .is_ok()
+    }
+
+    fn shell_name(&self) -> &str {
+        &self.shell
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_is_one_ascii_line() {
+        let line = encode_request(7, "Write-Output 'héllo'\nexit 0");
+
+        assert!(line.is_ascii());
+        assert_eq!(line.lines().count(), 1);
+        let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(request["id"], 7);
+        assert_eq!(request["script"], "Write-Output 'héllo'\nexit 0");
+    }
+
+    #[test]
+    fn test_exit_status_round_trips() {
+        assert!(exit_status(0).success());
+        assert_eq!(exit_status(3).code(), Some(3));
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::domain::PowerShellError;
+
+/// Wrap a command so its output objects, or the error record it raised, are
+/// printed as a single line of JSON
+///
+/// `-InputObject` keeps empty and single-item results as arrays on both
+/// Windows PowerShell 5.1 and PowerShell 7.
+pub fn query_script(cmd: &str) -> String {
+    format!(
+        r#"$ErrorActionPreference = 'Stop'
+$ProgressPreference = 'SilentlyContinue'
+try {{
+    $data = @(& {{
+{cmd}
+    }})
+    ConvertTo-Json -InputObject @{{ ok = $true; data = $data }} -Depth 4 -Compress
+}} catch {{
+    ConvertTo-Json -Compress -InputObject @{{ ok = $false; error = @{{
+        message = $_.Exception.Message
+        category = "$($_.CategoryInfo.Category)"
+        errorId = $_.FullyQualifiedErrorId
+        target = "$($_.TargetObject)"
+        exceptionType = $_.Exception.GetType().FullName
+    }} }}
+}}"#
+    )
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    ok: bool,
+    #[serde(default)]
+    data: serde_json::Value,
+    error: Option<PowerShellError>,
+}
+
+/// Parse the output of a [`query_script`], ignoring host output before the JSON line
+pub fn parse_query_output(output: &str) -> Result<serde_json::Value> {
+    let line = output
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .context("PowerShell query produced no output")?;
+    let envelope: Envelope = serde_json::from_str(line.trim())
+        .with_context(|| format!("PowerShell query returned invalid JSON: {}", line))?;
+
+    match (envelope.ok, envelope.error) {
+        (true, _) => Ok(match envelope.data {
+            serde_json::Value::Null => serde_json::Value::Array(vec![]),
+            data => data,
+        }),
+        (false, Some(error)) => Err(error.into()),
+        (false, None) => anyhow::bail!("PowerShell query failed without an error record"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ModuleInfo;
+
+    #[test]
+    fn test_parses_objects() {
+        let output = "WARNING: ignored\n{\"ok\":true,\"data\":[{\"Name\":\"posh-git\",\"Version\":\"1.1.0\",\"Path\":\"C:\\\\m\\\\posh-git.psd1\"}]}\n";
+        let modules: Vec<ModuleInfo> =
+            serde_json::from_value(parse_query_output(output).unwrap()).unwrap();
+
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].name, "posh-git");
+        assert_eq!(modules[0].version, "1.1.0");
+    }
+
+    #[test]
+    fn test_surfaces_error_record() {
+        let output = r#"{"ok":false,"error":{"message":"No match was found","category":"ObjectNotFound","errorId":"NoMatchFoundForCriteria,Microsoft.PowerShell.PackageManagement.Cmdlets.InstallPackage","target":"Nope","exceptionType":"System.Exception"}}"#;
+        let error = parse_query_output(output).unwrap_err();
+        let record = error.downcast_ref::<PowerShellError>().unwrap();
+
+        assert_eq!(record.category, "ObjectNotFound");
+        assert_eq!(record.target, "Nope");
+        assert!(record.error_id.starts_with("NoMatchFoundForCriteria"));
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use super::fixture::{save_fixture, CallKind, Interaction, Reply};
+use crate::domain::interfaces::ShellRunner;
+
+/// `ShellRunner` passing commands to another runner and recording every
+/// interaction to a fixture file that [`FakeShell::replay`](super::FakeShell::replay)
+/// can answer from
+///
+/// The fixture is rewritten after each call, so an interrupted run still
+/// leaves a usable file.
+pub struct RecordingShell {
+    inner: Arc<dyn ShellRunner>,
+    path: PathBuf,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl RecordingShell {
+    pub fn new(inner: Arc<dyn ShellRunner>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            interactions: Mutex::new(vec![]),
+        }
+    }
+
+    fn record(&self, kind: CallKind, command: &str, reply: Reply) {
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.push(Interaction {
+            kind,
+            command: command.to_string(),
+            reply,
+        });
+        if let Err(e) = save_fixture(&self.path, &interactions) {
+            warn!("{:#}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl ShellRunner for RecordingShell {
+    async fn run(&self, cmd: &str) -> Result<String> {
+        let result = self.inner.run(cmd).await;
+        self.record(CallKind::Run, cmd, Reply::from_result(&result));
+        result
+    }
+
+    async fn query(&self, cmd: &str) -> Result<serde_json::Value> {
+        let result = self.inner.query(cmd).await;
+        self.record(CallKind::Query, cmd, Reply::from_result(&result));
+        result
+    }
+
+    async fn exists(&self, command: &str) -> bool {
+        let exists = self.inner.exists(command).await;
+        self.record(CallKind::Exists, command, Reply::Exists(exists));
+        exists
+    }
+
+    fn shell_name(&self) -> &str {
+        self.inner.shell_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::shell::FakeShell;
+
+    #[tokio::test]
+    async fn test_recording_replays_identically() {
+        let path = std::env::temp_dir().join("setup_powershell_recording.json");
+        let live = Arc::new(
+            FakeShell::new()
+                .on_run("$PROFILE", "/home/me/profile.ps1")
+                .on_query("Get-Module", serde_json::json!([]))
+                .fail("Install-Module", "Repository unreachable")
+                .with_command("pwsh", true),
+        );
+        let recorder = RecordingShell::new(live, &path);
+        recorder.run("$PROFILE").await.unwrap();
+        recorder.query("Get-Module -Name 'z'").await.unwrap();
+        assert!(recorder.run("Install-Module -Name 'z'").await.is_err());
+        assert!(recorder.exists("pwsh").await);
+
+        let replay = FakeShell::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            replay.run("$PROFILE").await.unwrap(),
+            "/home/me/profile.ps1"
+        );
+        assert_eq!(
+            replay.query("Get-Module -Name 'z'").await.unwrap(),
+            serde_json::json!([])
+        );
+        let error = replay.run("Install-Module -Name 'z'").await.unwrap_err();
+        assert!(error.to_string().contains("Repository unreachable"));
+        assert!(replay.exists("pwsh").await);
+    }
+}
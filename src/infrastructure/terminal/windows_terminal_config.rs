@@ -1,8 +1,8 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use colored::Colorize;
 use std::path::PathBuf;
 use tokio::fs;
+use tracing::info;
 
 use crate::domain::interfaces::TerminalConfigurator;
 
@@ -94,11 +94,7 @@ impl TerminalConfigurator for WindowsTerminalConfig {
                 .await
                 .context("Failed to write settings.json")?;
 
-            println!(
-                "{} {}",
-                "✅".green(),
-                format!("Updated: {}", settings_path.display()).green()
-            );
+            info!("✅ Updated: {}", settings_path.display());
         }
 
         if !found {
@@ -3,6 +3,6 @@ pub mod cli;
 pub mod domain;
 pub mod infrastructure;
 
-pub use application::SetupService;
+pub use application::{BenchService, SetupService};
 pub use cli::SetupCli;
-pub use domain::{ProfileConfig, ProfilePreset};
+pub use domain::{NerdFont, ProfileConfig, ProfilePreset};
@@ -1,34 +1,111 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-use setup_powershell::domain::interfaces::{Installer, ShellRunner};
+use setup_powershell::application::guarded;
+use setup_powershell::domain::interfaces::{
+    Installer, ProfileRenderer, ProfileWriter, ShellRunner,
+};
+use setup_powershell::domain::{
+    BenchOptions, InstallOutcome, InstallScope, ModuleBackend, NerdFont, ProfileShell,
+    ShortcutGroup, Verbosity,
+};
 use setup_powershell::infrastructure::{
-    FontInstaller, FzfInstaller, HttpDownloader, ModuleInstaller, OhMyPoshInstaller,
-    PowerShellRunner, ProfileFsWriter, PwshInstaller, WindowsTerminalConfig,
+    is_elevated, ArchiveExtractor, FishRenderer, FzfInstaller, GithubToolInstaller, HttpDownloader,
+    LogSubscriber, ModuleInstaller, NerdFontInstaller, NushellRenderer, OhMyPoshInstaller,
+    PackageProviderInstaller, PosixRenderer, PowerShellRenderer, PowerShellRunner,
+    PowerShellSession, ProfileFsWriter, PwshInstaller, RecordingShell, RunLog, SettingsFile,
+    TempFile, WindowsTerminalConfig,
 };
-use setup_powershell::{SetupCli, SetupService};
+use setup_powershell::{BenchService, SetupCli, SetupService};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = SetupCli::new();
     cli.show_banner();
 
+    // Console output follows -q/-v/-vv; the run log always gets debug output
+    let log = Arc::new(RunLog::create()?);
+    let verbosity = Verbosity::from_args(std::env::args().skip(1));
+    let subscriber = LogSubscriber::new(verbosity).with_log(log.clone()).build();
+    tracing::subscriber::set_global_default(subscriber).context("Failed to initialize logging")?;
+
+    // `bench [-n RUNS] [--profile PATH]` measures the profile instead of running the setup
+    if let Some(options) = BenchOptions::from_args(std::env::args().skip(1))? {
+        return bench(&cli, options, log).await;
+    }
+
+    let settings = SettingsFile::discover().load().await?;
+
+    // Cancel in-flight steps on Ctrl-C; a second Ctrl-C exits immediately
+    let cancel = CancellationToken::new();
+    tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                println!("\n⚠ Cancelling, cleaning up... (press Ctrl-C again to force exit)");
+                cancel.cancel();
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    std::process::exit(130);
+                }
+            }
+        }
+    });
+
+    let timeouts = settings
+        .timeouts
+        .iter()
+        .map(|(name, secs)| (name.clone(), Duration::from_secs(*secs)))
+        .collect::<HashMap<_, _>>();
+
+    // Pick machine-wide or per-user installs from the process privileges
+    let scope = InstallScope::for_elevation(is_elevated().await);
+    // Modules stay per-user unless the settings ask for all users
+    let module_scope = settings.module_scope;
+    if module_scope == InstallScope::AllUsers && scope != InstallScope::AllUsers {
+        anyhow::bail!("module_scope all_users needs administrator or root rights");
+    }
+    cli.show_install_scope(scope, module_scope);
+
     // Determine which shell to use
     let use_pwsh = if !PowerShellRunner::pwsh().exists("pwsh").await {
         if cli.prompt_install_pwsh()? {
             // Install PowerShell 7
-            let downloader = Arc::new(HttpDownloader::new());
-            let pwsh_installer = PwshInstaller::new(downloader);
-            pwsh_installer.install().await?;
+            let pwsh_installer = PwshInstaller::new(
+                Arc::new(HttpDownloader::new()),
+                Arc::new(ArchiveExtractor::new()),
+            )
+            .with_scope(scope)
+            .with_log(log.clone());
+            let name = pwsh_installer.component_name();
+            let timeout = timeouts
+                .get(name)
+                .copied()
+                .unwrap_or_else(|| pwsh_installer.timeout());
+            let outcome = guarded(&cancel, name, timeout, pwsh_installer.install()).await?;
+            match outcome {
+                InstallOutcome::RestartRequired => {
+                    tracing::warn!(
+                        "Restart Windows to finish installing PowerShell 7, \
+                         then run this program again."
+                    );
+                    return Ok(());
+                }
+                InstallOutcome::Installed | InstallOutcome::AlreadyPresent => {}
+            }
 
             if !PowerShellRunner::pwsh().exists("pwsh").await {
-                println!("\n⚠ PowerShell 7 installed but not available yet.");
-                println!("Please restart your terminal and run this program again.\n");
+                tracing::warn!(
+                    "PowerShell 7 installed but not available yet. \
+                     Please restart your terminal and run this program again."
+                );
                 return Ok(());
             }
             true
         } else if PowerShellRunner::powershell().exists("powershell").await {
-            println!("\n🔄 Continuing with Windows PowerShell...\n");
+            tracing::info!("🔄 Continuing with Windows PowerShell...");
             false
         } else {
             anyhow::bail!("No PowerShell version found");
@@ -39,35 +116,106 @@ async fn main() -> Result<()> {
 
     // Select profile
     let preset = cli.select_preset()?;
-    let config = cli.build_config_from_preset(preset)?;
+    let font = settings
+        .font
+        .as_deref()
+        .map(|name| NerdFont::find(name).with_context(|| format!("Unknown Nerd Font: {}", name)))
+        .transpose()?;
+    let mut config = cli.build_config_from_preset(preset, font)?;
+    config.psreadline = config.psreadline.merge(settings.psreadline.clone());
+    config
+        .psreadline
+        .validate()
+        .context("Invalid psreadline settings")?;
+    config.groups = ShortcutGroup::merge_all(config.groups, settings.groups.clone());
+    for group in &config.groups {
+        group.validate()?;
+    }
+    for tool in &settings.tools {
+        tool.validate()?;
+    }
+    for (module, loading) in &settings.module_loading {
+        loading.validate(module)?;
+    }
+    config
+        .module_loading
+        .extend(settings.module_loading.clone());
 
-    // Build dependencies
-    let shell = Arc::new(if use_pwsh {
-        PowerShellRunner::pwsh()
-    } else {
-        PowerShellRunner::powershell()
-    });
+    // Build dependencies; one long-lived session runs every PowerShell command
+    let session = Arc::new(PowerShellSession::new(use_pwsh).with_log(log.clone()));
+    // SETUP_POWERSHELL_RECORD=<file> saves every command and reply as a test fixture
+    let shell: Arc<dyn ShellRunner> = match std::env::var_os("SETUP_POWERSHELL_RECORD") {
+        Some(path) => Arc::new(RecordingShell::new(session, path)),
+        None => session,
+    };
 
     let downloader = Arc::new(HttpDownloader::new());
-    let pwsh_installer = Arc::new(PwshInstaller::new(downloader.clone())) as Arc<dyn Installer>;
-    let font_installer = Arc::new(FontInstaller::meslo()) as Arc<dyn Installer>;
+    let extractor = Arc::new(ArchiveExtractor::new());
+    let pwsh_installer = Arc::new(
+        PwshInstaller::new(downloader.clone(), extractor.clone())
+            .with_scope(scope)
+            .with_log(log.clone()),
+    ) as Arc<dyn Installer>;
+    let font_installer = Arc::new(
+        NerdFontInstaller::from_font(config.font, downloader.clone(), extractor.clone())
+            .with_shell(shell.clone())
+            .with_log(log.clone()),
+    ) as Arc<dyn Installer>;
     let terminal_config = Arc::new(WindowsTerminalConfig::new());
     let profile_writer = Arc::new(ProfileFsWriter::new(shell.clone()));
+    // Detects the host and its PSReadLine once the modules are installed
+    let mut profile_renderers: Vec<Arc<dyn ProfileRenderer>> =
+        vec![Arc::new(PowerShellRenderer::new())];
+    profile_renderers.extend(settings.shells.iter().map(|shell| match shell {
+        ProfileShell::Bash => Arc::new(PosixRenderer::bash()) as Arc<dyn ProfileRenderer>,
+        ProfileShell::Zsh => Arc::new(PosixRenderer::zsh()),
+        ProfileShell::Fish => Arc::new(FishRenderer::new()),
+        ProfileShell::Nushell => Arc::new(NushellRenderer::new()),
+    }));
+
+    let tool_installers: Vec<Arc<dyn Installer>> = settings
+        .tools
+        .iter()
+        .map(|spec| {
+            Arc::new(
+                GithubToolInstaller::new(spec.clone(), downloader.clone(), extractor.clone())
+                    .with_log(log.clone()),
+            ) as Arc<dyn Installer>
+        })
+        .collect();
+
+    // Resolve the module backend once for all module installers
+    let module_backend = match settings.module_backend {
+        ModuleBackend::Auto => ModuleInstaller::detect_backend(shell.as_ref()).await,
+        backend => backend,
+    };
+    let module_bootstrapper = Arc::new(
+        PackageProviderInstaller::new(shell.clone(), module_backend).with_scope(module_scope),
+    ) as Arc<dyn Installer>;
+    let module_installer = |name: &str| {
+        let installer = ModuleInstaller::new(name, shell.clone())
+            .with_backend(module_backend)
+            .with_scope(module_scope);
+        match settings.module_versions.get(name) {
+            Some(version) => installer.with_minimum_version(version.clone()),
+            None => installer,
+        }
+    };
 
     // Build module installers
     let mut module_installers: Vec<Arc<dyn Installer>> = vec![];
 
     // Add oh-my-posh installer first
-    module_installers.push(Arc::new(OhMyPoshInstaller::new()));
+    module_installers.push(Arc::new(OhMyPoshInstaller::new().with_log(log.clone())));
 
     for plugin in &config.plugins {
         let installer: Arc<dyn Installer> = match plugin.as_str() {
             "PSFzf" => {
                 // PSFzf requires fzf, so add both
-                module_installers.push(Arc::new(FzfInstaller::new()));
-                Arc::new(ModuleInstaller::new(plugin.clone(), shell.clone()))
+                module_installers.push(Arc::new(FzfInstaller::new().with_log(log.clone())));
+                Arc::new(module_installer(plugin))
             }
-            _ => Arc::new(ModuleInstaller::new(plugin.clone(), shell.clone())),
+            _ => Arc::new(module_installer(plugin)),
         };
         module_installers.push(installer);
     }
@@ -79,7 +227,15 @@ async fn main() -> Result<()> {
         .font_installer(font_installer)
         .terminal_config(terminal_config)
         .profile_writer(profile_writer)
+        .profile_renderers(profile_renderers)
+        .module_bootstrapper(module_bootstrapper)
         .module_installers(module_installers)
+        .tool_installers(tool_installers)
+        .hooks(settings.hooks.clone())
+        .collision_policy(settings.alias_collisions)
+        .timeouts(timeouts)
+        .cancel(cancel)
+        .log_path(log.path().to_path_buf())
         .build();
 
     // Run setup
@@ -87,3 +243,39 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Measure shell startup with the current or given profile
+async fn bench(cli: &SetupCli, options: BenchOptions, log: Arc<RunLog>) -> Result<()> {
+    let use_pwsh = PowerShellRunner::pwsh().exists("pwsh").await;
+    // A runner, not a session, so that every measurement starts a new process
+    let shell = Arc::new(PowerShellRunner::new(use_pwsh).with_log(log));
+    let path = match options.profile {
+        Some(path) => path,
+        None => {
+            ProfileFsWriter::new(shell.clone())
+                .get_profile_path()
+                .await?
+        }
+    };
+    let profile = tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("No profile to measure at {}", path.display()))?;
+
+    tracing::info!(
+        "⏱ Launching {} {} times per variant...",
+        shell.shell_name(),
+        options.runs
+    );
+    // Next to the profile so that its $PSScriptRoot still applies; removed on drop
+    let script = TempFile::new(path.with_extension("bench.ps1"));
+    let report = BenchService::builder()
+        .shell(shell.clone())
+        .writer(Arc::new(ProfileFsWriter::new(shell)))
+        .runs(options.runs)
+        .build()
+        .run(&profile, script.path())
+        .await?;
+    cli.show_bench_report(&path, &report);
+
+    Ok(())
+}